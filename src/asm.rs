@@ -1,15 +1,23 @@
+use std::vec;
+
 pub mod ia32 {
   pub use asm::ia32::base::*;
   pub use asm::ia32::basic::*;
   pub use asm::ia32::math::*;
   pub use asm::ia32::branching::*;
   pub use asm::ia32::fp::*;
+  pub use asm::ia32::atomic::*;
+  pub use asm::ia32::string::*;
+  pub use asm::ia32::simd::*;
 
   pub mod base;
   pub mod basic;
   pub mod math;
   pub mod branching;
   pub mod fp;
+  pub mod atomic;
+  pub mod string;
+  pub mod simd;
 }
 
 pub mod x64 {
@@ -18,12 +26,22 @@ pub mod x64 {
   pub use asm::x64::math::*;
   pub use asm::x64::branching::*;
   pub use asm::x64::fp::*;
+  pub use asm::x64::bmi::*;
+  pub use asm::x64::atomic::*;
+  pub use asm::x64::string::*;
+  pub use asm::x64::simd::*;
+  pub use asm::x64::avx::*;
 
   pub mod base;
   pub mod basic;
   pub mod math;
   pub mod branching;
   pub mod fp;
+  pub mod bmi;
+  pub mod atomic;
+  pub mod string;
+  pub mod simd;
+  pub mod avx;
 }
 
 #[deriving(Clone)]
@@ -48,6 +66,7 @@ pub struct RelocationInfo {
   kind: RelocationKind,
   size: RelocationSize,
   nudge: int,
+  relax: bool,
   from: AsmOffset,
   to: AsmOffset
 }
@@ -56,14 +75,24 @@ pub struct LabelRef {
   kind: RelocationKind,
   size: RelocationSize,
   nudge: int,
+  relax: bool,
   from: AsmOffset
 }
 
 pub struct Label {
   offset: Option<AsmOffset>,
-  refs: ~[LabelRef]
+  refs: ~[LabelRef],
+  relax: bool
+}
+
+pub struct AsmError {
+  mnemonic: &'static str,
+  operands: ~[&'static str],
+  reason: &'static str
 }
 
+pub type AsmResult = Result<(), AsmError>;
+
 pub trait AsmBuffer {
   fn emitb(&mut self, b: u8);
   fn emitw(&mut self, w: u16);
@@ -75,15 +104,37 @@ pub trait AsmBuffer {
 
 pub trait AsmHelper {
   fn bind(&mut self, l: &mut Label);
+  fn is_short_jump(&self, l: &Label, len: uint) -> bool;
   fn emit_use(&mut self,
               l: &mut Label,
               kind: RelocationKind,
               size: RelocationSize,
               nudge: int);
+  fn emit_jump_use(&mut self, l: &mut Label);
 }
 
 impl Label {
-  pub fn new() -> Label { Label { offset: None, refs: ~[] } }
+  pub fn new() -> Label { Label { offset: None, refs: ~[], relax: false } }
+
+  // Jumps to relaxed labels use rel8 form whenever possible, see `relax()`
+  pub fn relaxed() -> Label { Label { offset: None, refs: ~[], relax: true } }
+}
+
+impl ToStr for AsmError {
+  fn to_str(&self) -> ~str {
+    fmt!("%s(%s): %s",
+         self.mnemonic,
+         self.operands.connect(", "),
+         self.reason)
+  }
+}
+
+// Used by infallible emitters to turn error into task failure
+pub fn or_fail(r: AsmResult) {
+  match r {
+    Ok(()) => (),
+    Err(err) => fail!(err.to_str())
+  }
 }
 
 impl<A: AsmBuffer> AsmHelper for A {
@@ -94,6 +145,7 @@ impl<A: AsmBuffer> AsmHelper for A {
         kind: r.kind,
         size: r.size,
         nudge: r.nudge,
+        relax: r.relax,
         from: r.from,
         to: l.offset.unwrap()
       };
@@ -101,33 +153,166 @@ impl<A: AsmBuffer> AsmHelper for A {
     }
   }
 
+  fn is_short_jump(&self, l: &Label, len: uint) -> bool {
+    if !l.relax {
+      return false;
+    }
+
+    // Forward jumps are optimistically short and are grown by `relax()`
+    match l.offset {
+      None => true,
+      Some(AsmOffset(to)) => {
+        let AsmOffset(from) = self.offset();
+        let delta = (to as int) - ((from + len) as int);
+        -128 <= delta && delta <= 127
+      }
+    }
+  }
+
   fn emit_use(&mut self,
               l: &mut Label,
               kind: RelocationKind,
               size: RelocationSize,
               nudge: int) {
-    if l.offset.is_none() {
-      l.refs.push(LabelRef {
-        kind: kind,
-        size: size,
-        nudge: nudge,
-        from: self.offset()
-      });
-    } else {
-      let info = RelocationInfo {
-        kind: kind,
-        size: size,
-        nudge: nudge,
-        from: self.offset(),
-        to: l.offset.unwrap()
+    use_label(self, l, kind, size, nudge, false);
+  }
+
+  // rel8 of jmp or jcc, the only uses `relax()` knows how to grow
+  fn emit_jump_use(&mut self, l: &mut Label) {
+    let relax = l.relax;
+    use_label(self, l, RelocRelative, RelocByte, -1, relax);
+  }
+}
+
+fn use_label<A: AsmBuffer>(m: &mut A,
+                           l: &mut Label,
+                           kind: RelocationKind,
+                           size: RelocationSize,
+                           nudge: int,
+                           relax: bool) {
+  if l.offset.is_none() {
+    l.refs.push(LabelRef {
+      kind: kind,
+      size: size,
+      nudge: nudge,
+      relax: relax,
+      from: m.offset()
+    });
+  } else {
+    let info = RelocationInfo {
+      kind: kind,
+      size: size,
+      nudge: nudge,
+      relax: relax,
+      from: m.offset(),
+      to: l.offset.unwrap()
+    };
+    m.relocate(&info);
+  }
+  match size {
+    RelocByte => m.emitb(0),
+    RelocWord => m.emitw(0),
+    RelocLong => m.emitl(0),
+    RelocQuad => m.emitq(0)
+  }
+}
+
+// Grows relaxed rel8 jumps that can't reach their targets into rel32 form,
+// shifting all code and relocations after them. Should be called once all
+// code is emitted and all labels are bound. Returns where and by how much
+// the code grew, offsets taken before are translated by `relaxed_offset()`.
+pub fn relax(code: &mut ~[u8],
+             infos: &mut ~[RelocationInfo]) -> ~[(uint, uint)] {
+  let mut growth = ~[];
+
+  // Growing may push other jumps out of range, so repeat until it settles
+  loop {
+    // Indices of out of range jumps, sorted by offset
+    let mut grown: ~[uint] = ~[];
+    let mut i = 0;
+    while i < infos.len() {
+      let AsmOffset(from) = infos[i].from;
+      let AsmOffset(to) = infos[i].to;
+      let delta = (to as int) - (from as int) + infos[i].nudge;
+
+      if infos[i].relax && (delta < -128 || 127 < delta) {
+        let mut j = grown.len();
+        while j > 0 {
+          let AsmOffset(prev) = infos[grown[j - 1]].from;
+          if prev < from {
+            break;
+          }
+          j -= 1;
+        }
+        grown.insert(j, i);
+      }
+      i += 1;
+    }
+
+    if grown.is_empty() {
+      break;
+    }
+
+    // Rebuild the code once for all of them
+    let mut res = vec::with_capacity(code.len() + 4 * grown.len());
+    let mut moved = ~[];
+    let mut pass = ~[];
+    let mut last = 0;
+    for grown.iter().advance |&i| {
+      let AsmOffset(from) = infos[i].from;
+
+      // jmp rel8 => jmp rel32, jcc rel8 => jcc rel32
+      let op = code[from - 1];
+      let long = match op {
+        0xeb => ~[0xe9],
+        0x70 .. 0x7f => ~[0x0f, 0x80 | (op & 0xf)],
+        _ => fail!(fmt!("relax: %x at %u is not a short jump",
+                        op as uint,
+                        from - 1))
       };
-      self.relocate(&info);
+      res.push_all(code.slice(last, from - 1));
+      res.push_all(long);
+      moved.push(res.len());
+      res.push_all(&[0, 0, 0, 0]);
+      last = from + 1;
+
+      // Last to first, so replaying it never moves an earlier jump
+      pass.unshift((from, long.len() + 4 - 2));
     }
-    match size {
-      RelocByte => self.emitb(0),
-      RelocWord => self.emitw(0),
-      RelocLong => self.emitl(0),
-      RelocQuad => self.emitq(0)
+    res.push_all(code.slice(last, code.len()));
+    *code = res;
+
+    let mut j = 0;
+    while j < infos.len() {
+      infos[j].from = relaxed_offset(pass, infos[j].from);
+      infos[j].to = relaxed_offset(pass, infos[j].to);
+      j += 1;
+    }
+
+    let mut k = 0;
+    while k < grown.len() {
+      let i = grown[k];
+      infos[i].from = AsmOffset(moved[k]);
+      infos[i].size = RelocLong;
+      infos[i].nudge = -4;
+      infos[i].relax = false;
+      k += 1;
+    }
+
+    growth.push_all(pass);
+  }
+  growth
+}
+
+// Translates an offset taken before `relax()` using the growth it returned
+pub fn relaxed_offset(growth: &[(uint, uint)],
+                      offset: AsmOffset) -> AsmOffset {
+  let AsmOffset(start) = offset;
+  let mut off = start;
+  for growth.iter().advance |&(from, size)| {
+    if off > from {
+      off += size;
     }
   }
+  AsmOffset(off)
 }
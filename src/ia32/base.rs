@@ -5,7 +5,8 @@ pub enum Operand {
   _Operation(u8),
   R(Register),
   D(DoubleRegister),
-  M(Register, u32),
+  M(Register, i32),
+  MI(Register, Register, Scale, i32),
   Byte(u8),
   Word(u16),
   Long(u32),
@@ -34,6 +35,42 @@ pub enum DoubleRegister {
   xmm7 = 7
 }
 
+pub enum Scale {
+  Scale1 = 0,
+  Scale2 = 1,
+  Scale4 = 2,
+  Scale8 = 3
+}
+
+pub enum OperandSize {
+  Size8,
+  Size16,
+  Size32
+}
+
+// Group-1 arithmetic, the value is the /digit of the opcode
+pub enum AluOp {
+  AluAdd = 0,
+  AluOr = 1,
+  AluAdc = 2,
+  AluSbb = 3,
+  AluAnd = 4,
+  AluSub = 5,
+  AluXor = 6,
+  AluCmp = 7
+}
+
+static ALU_MNEMONICS: [[&'static str, ..3], ..8] = [
+  ["addb", "addw", "addl"],
+  ["orb", "orw", "orl"],
+  ["adcb", "adcw", "adcl"],
+  ["sbbb", "sbbw", "sbbl"],
+  ["andb", "andw", "andl"],
+  ["subb", "subw", "subl"],
+  ["xorb", "xorw", "xorl"],
+  ["cmpb", "cmpw", "cmpl"]
+];
+
 pub enum JumpCondition {
   IfZero,
   IfNotZero,
@@ -44,7 +81,76 @@ pub enum JumpCondition {
   IfGreater,
   IfLess,
   IfGreaterOrEqual,
-  IfLessOrEqual
+  IfLessOrEqual,
+  IfBelow,
+  IfBelowOrEqual,
+  IfAbove,
+  IfAboveOrEqual,
+  IfSign,
+  IfNotSign,
+  IfParity,
+  IfNotParity,
+  IfCarry,
+  IfNotCarry
+}
+
+pub fn invalid(mnemonic: &'static str,
+               operands: &[Operand],
+               reason: &'static str) -> AsmResult {
+  Err(AsmError {
+    mnemonic: mnemonic,
+    operands: operands.map(|op| op.kind()),
+    reason: reason
+  })
+}
+
+impl JumpCondition {
+  // Low nibble of jcc opcodes
+  fn code(&self) -> u8 {
+    match *self {
+      IfOverlow => 0x0,
+      IfNoOverlow => 0x1,
+      IfBelow | IfCarry => 0x2,
+      IfAboveOrEqual | IfNotCarry => 0x3,
+      IfZero | IfEqual => 0x4,
+      IfNotZero | IfNotEqual => 0x5,
+      IfBelowOrEqual => 0x6,
+      IfAbove => 0x7,
+      IfSign => 0x8,
+      IfNotSign => 0x9,
+      IfParity => 0xa,
+      IfNotParity => 0xb,
+      IfLess => 0xc,
+      IfGreaterOrEqual => 0xd,
+      IfLessOrEqual => 0xe,
+      IfGreater => 0xf
+    }
+  }
+}
+
+impl AluOp {
+  fn mnemonic(&self, size: OperandSize) -> &'static str {
+    ALU_MNEMONICS[*self as uint][size as uint]
+  }
+}
+
+impl OperandSize {
+  // Full-size opcodes are byte ones with the lowest bit set
+  fn opcode(&self, op: u8) -> u8 {
+    match *self {
+      Size8 => op,
+      _ => op | 1
+    }
+  }
+
+  fn is_imm(&self, imm: &Operand) -> bool {
+    match (*self, *imm) {
+      (Size8, Byte(_)) => true,
+      (Size16, Word(_)) => true,
+      (Size32, Long(_)) => true,
+      _ => false
+    }
+  }
 }
 
 impl Register {
@@ -57,6 +163,20 @@ impl DoubleRegister {
 
 pub trait AsmIA32Helper {
   fn emit_modrm(&mut self, r: Operand, rm: Operand);
+  fn emit_imm(&mut self, imm: Operand);
+  fn emit_size_prefix(&mut self, size: OperandSize);
+  fn emit_sse(&mut self,
+              prefix: Option<u8>,
+              escape: Option<u8>,
+              op: u8,
+              r: Operand,
+              rm: Operand,
+              imm: Operand);
+  fn emit_alu(&mut self,
+              op: AluOp,
+              size: OperandSize,
+              dst: Operand,
+              src: Operand) -> AsmResult;
 }
 
 pub trait AsmIA32 {
@@ -67,17 +187,48 @@ pub trait AsmIA32 {
 impl Operand {
   fn is_reg(&self) -> bool { match self { &R(_) => true, _ => false } }
   fn is_dreg(&self) -> bool { match self { &D(_) => true, _ => false } }
-  fn is_mem(&self) -> bool { match self { &M(_, _) => true, _ => false } }
+  fn is_mem(&self) -> bool {
+    match self {
+      _ if self.has_stack_index() => false,
+      &M(_, _) | &MI(_, _, _, _) => true,
+      _ => false
+    }
+  }
+
+  // esp in the index field means "no index"
+  fn has_stack_index(&self) -> bool {
+    match self { &MI(_, esp, _, _) => true, _ => false }
+  }
   fn is_rm(&self) -> bool { self.is_reg() || self.is_mem() }
   fn is_dm(&self) -> bool { self.is_dreg() || self.is_mem() }
 
+  // esp, ebp, esi and edi are encoded as ah, ch, dh and bh
+  fn has_byte_form(&self) -> bool {
+    match self { &R(ref r) => r.val() < 4, _ => true }
+  }
+
   fn is_operation(&self) -> bool {
     match self { &_Operation(_) => true, _ => false }
   }
 
+  fn kind(&self) -> &'static str {
+    match self {
+      &Empty => "Empty",
+      &_Operation(_) => "_Operation",
+      &R(_) => "R",
+      &D(_) => "D",
+      &M(_, _) => "M",
+      &MI(_, _, _, _) => "MI",
+      &Byte(_) => "Byte",
+      &Word(_) => "Word",
+      &Long(_) => "Long",
+      &Quad(_) => "Quad"
+    }
+  }
+
   fn val(&self) -> u8 {
     match self {
-      &R(ref r) | &M(ref r, _) => r.val(),
+      &R(ref r) | &M(ref r, _) | &MI(ref r, _, _, _) => r.val(),
       &D(ref d) => d.val(),
       &_Operation(op) => op & 7,
       _ => 0
@@ -91,14 +242,40 @@ impl<M: AsmBuffer> AsmIA32Helper for M {
     let rbit = r.val() << 3;
 
     match rm {
-      M(ref rm, 0) => self.emitb(rbit | rm.val()),
-      M(ref rm, b) if b <= 0xff => {
-        self.emitb(0b0100_0000 | rbit | rm.val());
-        self.emitb(b as u8)
-      },
-      M(ref rm, l) => {
-        self.emitb(0b1000_0000 | rbit | rm.val());
-        self.emitl(l)
+      M(ref base, disp) | MI(ref base, _, _, disp) => {
+        // ebp with mod=00 means disp32 without a base
+        let mode = if disp == 0 && base.val() != 0b101 {
+          0b00
+        } else if -128 <= disp && disp <= 127 {
+          0b01
+        } else {
+          0b10
+        };
+
+        let sib = match rm {
+          MI(_, ref index, scale, _) => {
+            // esp in the index field means "no index"
+            assert!(index.val() != esp.val());
+            Some(((scale as u8) << 6) | (index.val() << 3) | base.val())
+          },
+          // esp can only be used as a base through SIB
+          _ if base.val() == 0b100 => Some(0x24),
+          _ => None
+        };
+
+        match sib {
+          Some(sib) => {
+            self.emitb((mode << 6) | rbit | 0b100);
+            self.emitb(sib);
+          },
+          None => self.emitb((mode << 6) | rbit | base.val())
+        }
+
+        match mode {
+          0b01 => self.emitb(disp as u8),
+          0b10 => self.emitl(disp as u32),
+          _ => ()
+        }
       },
       R(ref rm) => self.emitb(0b1100_0000 | rbit | rm.val()),
       D(ref rm) => self.emitb(0b1100_0000 | rbit | rm.val()),
@@ -110,6 +287,96 @@ impl<M: AsmBuffer> AsmIA32Helper for M {
       _ => fail!()
     }
   }
+
+  fn emit_imm(&mut self, imm: Operand) {
+    match imm {
+      Byte(b) => self.emitb(b),
+      Word(w) => self.emitw(w),
+      Long(l) => self.emitl(l),
+      Quad(q) => self.emitq(q),
+      Empty => (),
+      _ => fail!()
+    }
+  }
+
+  fn emit_size_prefix(&mut self, size: OperandSize) {
+    match size {
+      Size16 => self.emitb(0x66),
+      _ => ()
+    }
+  }
+
+  fn emit_sse(&mut self,
+              prefix: Option<u8>,
+              escape: Option<u8>,
+              op: u8,
+              r: Operand,
+              rm: Operand,
+              imm: Operand) {
+    match prefix {
+      Some(p) => self.emitb(p),
+      None => ()
+    }
+
+    // 0F 38 and 0F 3A opcodes carry the middle byte in `escape`
+    self.emitb(0x0f);
+    match escape {
+      Some(e) => self.emitb(e),
+      None => ()
+    }
+    self.emitb(op);
+    self.emit_modrm(r, rm);
+    self.emit_imm(imm);
+  }
+
+  fn emit_alu(&mut self,
+              op: AluOp,
+              size: OperandSize,
+              dst: Operand,
+              src: Operand) -> AsmResult {
+    let name = op.mnemonic(size);
+    let digit = op as u8;
+    let op = digit << 3;
+
+    match size {
+      Size8 if !dst.has_byte_form() || !src.has_byte_form() => {
+        return invalid(name, &[dst, src], "no byte form of the register");
+      },
+      _ => ()
+    }
+
+    match (dst, src) {
+      (R(eax), _) if size.is_imm(&src) => {
+        self.emit_size_prefix(size);
+        self.emitb(size.opcode(op | 0x04));
+        self.emit_imm(src);
+      },
+      (_, Byte(b)) if dst.is_rm() && !size.is_imm(&src) => {
+        self.emit_size_prefix(size);
+        self.emitb(0x83);
+        self.emit_modrm(_Operation(digit), dst);
+        self.emitb(b);
+      },
+      (_, _) if dst.is_rm() && size.is_imm(&src) => {
+        self.emit_size_prefix(size);
+        self.emitb(size.opcode(0x80));
+        self.emit_modrm(_Operation(digit), dst);
+        self.emit_imm(src);
+      },
+      (R(_), _) if src.is_rm() => {
+        self.emit_size_prefix(size);
+        self.emitb(size.opcode(op | 0x02));
+        self.emit_modrm(dst, src);
+      },
+      (_, R(_)) if dst.is_rm() => {
+        self.emit_size_prefix(size);
+        self.emitb(size.opcode(op));
+        self.emit_modrm(src, dst);
+      },
+      _ => return invalid(name, &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
 }
 
 impl<M: AsmBuffer+AsmIA32Helper> AsmIA32 for M {
@@ -6,14 +6,31 @@ pub trait AsmIA32Basic {
   fn movlzxb(&mut self, dst: Operand, src: Operand);
   fn movlzxl(&mut self, dst: Operand, src: Operand);
   fn movl_proc(&mut self, dst: Operand, l: &mut Label);
+  fn leal(&mut self, dst: Operand, src: Operand);
   fn xchgl(&mut self, dst: Operand, src: Operand);
   fn pushl(&mut self, op: Operand);
   fn popl(&mut self, op: Operand);
   fn ret(&mut self, r: Operand);
+  fn movlsxb(&mut self, dst: Operand, src: Operand);
+  fn movlsxw(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Basic for A {
-  fn movl(&mut self, dst: Operand, src: Operand) {
+pub trait AsmIA32BasicTry {
+  fn try_movl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movlzxb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movlzxl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movl_proc(&mut self, dst: Operand, l: &mut Label) -> AsmResult;
+  fn try_leal(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xchgl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pushl(&mut self, op: Operand) -> AsmResult;
+  fn try_popl(&mut self, op: Operand) -> AsmResult;
+  fn try_ret(&mut self, r: Operand) -> AsmResult;
+  fn try_movlsxb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movlsxw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmIA32Helper> AsmIA32BasicTry for A {
+  fn try_movl(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(_), _) if src.is_rm() => {
         self.emitb(0x8b);
@@ -32,43 +49,58 @@ impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Basic for A {
         self.emitb(0xb8 | dst.val());
         self.emitl(l);
       },
-      _ => fail!()
+      _ => return invalid("movl", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movlzxb(&mut self, dst: Operand, src: Operand) {
+  fn try_movlzxb(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(_), _) if src.is_rm() => {
         self.emitb(0x0f);
         self.emitb(0xb6);
         self.emit_modrm(dst, src);
       },
-      _ => fail!()
+      _ => return invalid("movlzxb", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movlzxl(&mut self, dst: Operand, src: Operand) {
+  fn try_movlzxl(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(_), _) if src.is_rm() => {
         self.emitb(0x0f);
         self.emitb(0xb7);
         self.emit_modrm(dst, src);
       },
-      _ => fail!()
+      _ => return invalid("movlzxl", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movl_proc(&mut self, dst: Operand, l: &mut Label) {
+  fn try_movl_proc(&mut self, dst: Operand, l: &mut Label) -> AsmResult {
     match dst {
       R(_) => {
         self.emitb(0xb8 | dst.val());
         self.emit_use(l, RelocAbsolute, RelocLong, 0);
       },
-      _ => fail!()
+      _ => return invalid("movl_proc", &[dst], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn xchgl(&mut self, dst: Operand, src: Operand) {
+  fn try_leal(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_mem() => {
+        self.emitb(0x8d);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("leal", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_xchgl(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(eax), R(_)) => {
         self.emitb(0x90 | src.val());
@@ -84,17 +116,18 @@ impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Basic for A {
         self.emitb(0x87);
         self.emit_modrm(src, dst);
       },
-      _ => fail!()
+      _ => return invalid("xchgl", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn pushl(&mut self, op: Operand) {
+  fn try_pushl(&mut self, op: Operand) -> AsmResult {
     match op {
       R(_) => {
         self.emitb(0xff);
         self.emit_modrm(_Operation(6), op);
       },
-      M(_, _) => {
+      _ if op.is_mem() => {
         self.emitb(0xff);
         self.emit_modrm(_Operation(6), op);
       },
@@ -106,31 +139,107 @@ impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Basic for A {
         self.emitb(0x68);
         self.emitl(l);
       },
-      _ => fail!()
+      _ => return invalid("pushl", &[op], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn popl(&mut self, op: Operand) {
+  fn try_popl(&mut self, op: Operand) -> AsmResult {
     match op {
       R(_) => {
         self.emitb(0x58 | op.val());
       },
-      M(_, _) => {
+      _ if op.is_mem() => {
         self.emitb(0x8f);
         self.emit_modrm(_Operation(0), op);
       },
-      _ => fail!()
+      _ => return invalid("popl", &[op], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn ret(&mut self, r: Operand) {
+  fn try_ret(&mut self, r: Operand) -> AsmResult {
     match r {
       Empty => self.emitb(0xc3),
       Word(w) => {
         self.emitb(0xc2);
         self.emitw(w as u16);
       },
-      _ => fail!()
+      _ => return invalid("ret", &[r], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_movlsxb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if !src.has_byte_form() => {
+        return invalid("movlsxb", &[dst, src], "no byte form of the register");
+      },
+      (R(_), _) if src.is_rm() => {
+        self.emitb(0x0f);
+        self.emitb(0xbe);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("movlsxb", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_movlsxw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_rm() => {
+        self.emitb(0x0f);
+        self.emitb(0xbf);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("movlsxw", &[dst, src], "unsupported operands")
     }
+    Ok(())
+  }
+}
+
+impl<A: AsmBuffer+AsmIA32Helper+AsmIA32BasicTry> AsmIA32Basic for A {
+  fn movl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movl(dst, src));
+  }
+
+  fn movlzxb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movlzxb(dst, src));
+  }
+
+  fn movlzxl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movlzxl(dst, src));
+  }
+
+  fn movl_proc(&mut self, dst: Operand, l: &mut Label) {
+    or_fail(self.try_movl_proc(dst, l));
+  }
+
+  fn leal(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_leal(dst, src));
+  }
+
+  fn xchgl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xchgl(dst, src));
+  }
+
+  fn pushl(&mut self, op: Operand) {
+    or_fail(self.try_pushl(op));
+  }
+
+  fn popl(&mut self, op: Operand) {
+    or_fail(self.try_popl(op));
+  }
+
+  fn ret(&mut self, r: Operand) {
+    or_fail(self.try_ret(r));
+  }
+
+  fn movlsxb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movlsxb(dst, src));
+  }
+
+  fn movlsxw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movlsxw(dst, src));
   }
 }
@@ -6,12 +6,32 @@ pub trait AsmIA32Branching {
   fn cmpl(&mut self, dst: Operand, src: Operand);
   fn jmp(&mut self, target: Operand);
   fn jmpl(&mut self, l: &mut Label);
+  fn jmpb(&mut self, l: &mut Label);
   fn jccl(&mut self, c: JumpCondition, l: &mut Label);
+  fn jccb(&mut self, c: JumpCondition, l: &mut Label);
   fn call(&mut self, target: Operand);
+  fn cmovl(&mut self, c: JumpCondition, dst: Operand, src: Operand);
+  fn setcc(&mut self, c: JumpCondition, dst: Operand);
+  fn cmpw(&mut self, dst: Operand, src: Operand);
+  fn cmpb(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Branching for A {
-  fn testl(&mut self, dst: Operand, src: Operand) {
+pub trait AsmIA32BranchingTry {
+  fn try_testl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_jmp(&mut self, target: Operand) -> AsmResult;
+  fn try_call(&mut self, target: Operand) -> AsmResult;
+  fn try_cmovl(&mut self,
+               c: JumpCondition,
+               dst: Operand,
+               src: Operand) -> AsmResult;
+  fn try_setcc(&mut self, c: JumpCondition, dst: Operand) -> AsmResult;
+  fn try_cmpw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmIA32Helper> AsmIA32BranchingTry for A {
+  fn try_testl(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(eax), Long(l)) => {
         self.emitb(0xa9);
@@ -26,67 +46,129 @@ impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Branching for A {
         self.emitb(0x85);
         self.emit_modrm(src, dst);
       },
-      _ => fail!()
+      _ => return invalid("testl", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn cmpl(&mut self, dst: Operand, src: Operand) {
+  fn try_cmpl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size32, dst, src)
+  }
+
+  fn try_jmp(&mut self, target: Operand) -> AsmResult {
+    if !target.is_rm() {
+      return invalid("jmp", &[target], "expected register or memory");
+    }
+    self.emitb(0xff);
+    self.emit_modrm(Empty, target);
+    Ok(())
+  }
+
+  fn try_call(&mut self, target: Operand) -> AsmResult {
+    if !target.is_rm() {
+      return invalid("call", &[target], "expected register or memory");
+    }
+    self.emitb(0xff);
+    self.emit_modrm(_Operation(2), target);
+    Ok(())
+  }
+
+  fn try_cmovl(&mut self,
+               c: JumpCondition,
+               dst: Operand,
+               src: Operand) -> AsmResult {
     match (dst, src) {
-      (R(eax), Long(l)) => {
-        self.emitb(0x3d);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(7), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(7), dst);
-        self.emitl(l);
-      },
       (R(_), _) if src.is_rm() => {
-        self.emitb(0x3b);
+        self.emitb(0x0f);
+        self.emitb(0x40 | c.code());
         self.emit_modrm(dst, src);
       },
-      (_, R(_)) if dst.is_rm() => {
-        self.emitb(0x39);
-        self.emit_modrm(src, dst);
+      _ => return invalid("cmovl", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_setcc(&mut self, c: JumpCondition, dst: Operand) -> AsmResult {
+    match dst {
+      _ if !dst.has_byte_form() => {
+        return invalid("setcc", &[dst], "no byte form of the register");
+      },
+      _ if dst.is_rm() => {
+        self.emitb(0x0f);
+        self.emitb(0x90 | c.code());
+        self.emit_modrm(_Operation(0), dst);
       },
-      _ => fail!()
+      _ => return invalid("setcc", &[dst], "expected register or memory")
     }
+    Ok(())
+  }
+
+  fn try_cmpw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size16, dst, src)
+  }
+
+  fn try_cmpb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size8, dst, src)
+  }
+}
+
+impl<A: AsmBuffer+AsmIA32Helper+AsmIA32BranchingTry> AsmIA32Branching for A {
+  fn testl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_testl(dst, src));
+  }
+
+  fn cmpl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpl(dst, src));
   }
 
   fn jmp(&mut self, target: Operand) {
-    self.emitb(0xff);
-    self.emit_modrm(Empty, target);
+    or_fail(self.try_jmp(target));
   }
 
   fn jmpl(&mut self, l: &mut Label) {
+    if self.is_short_jump(l, 2) {
+      return self.jmpb(l);
+    }
     self.emitb(0xe9);
     self.emit_use(l, RelocRelative, RelocLong, -4);
   }
 
+  fn jmpb(&mut self, l: &mut Label) {
+    self.emitb(0xeb);
+    self.emit_jump_use(l);
+  }
+
   fn jccl(&mut self, c: JumpCondition, l: &mut Label) {
+    if self.is_short_jump(l, 2) {
+      return self.jccb(c, l);
+    }
     self.emitb(0x0f);
-    self.emitb(match c {
-      IfZero => 0x84,
-      IfNotZero => 0x85,
-      IfOverlow => 0x80,
-      IfNoOverlow => 0x81,
-      IfEqual => 0x84,
-      IfNotEqual => 0x85,
-      IfGreater => 0x8f,
-      IfLess => 0x8c,
-      IfGreaterOrEqual => 0x8d,
-      IfLessOrEqual => 0x8e
-    });
+    self.emitb(0x80 | c.code());
     self.emit_use(l, RelocRelative, RelocLong, -4);
   }
 
+  fn jccb(&mut self, c: JumpCondition, l: &mut Label) {
+    self.emitb(0x70 | c.code());
+    self.emit_jump_use(l);
+  }
+
   fn call(&mut self, target: Operand) {
-    self.emitb(0xff);
-    self.emit_modrm(_Operation(2), target);
+    or_fail(self.try_call(target));
+  }
+
+  fn cmovl(&mut self, c: JumpCondition, dst: Operand, src: Operand) {
+    or_fail(self.try_cmovl(c, dst, src));
+  }
+
+  fn setcc(&mut self, c: JumpCondition, dst: Operand) {
+    or_fail(self.try_setcc(c, dst));
+  }
+
+  fn cmpw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpw(dst, src));
+  }
+
+  fn cmpb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpb(dst, src));
   }
 }
@@ -8,6 +8,70 @@ pub enum RoundMode {
   RoundToward = 3
 }
 
+// cmpsd predicate, stored in the immediate byte
+pub enum CmpPredicate {
+  CmpEq = 0,
+  CmpLt = 1,
+  CmpLe = 2,
+  CmpUnord = 3,
+  CmpNeq = 4,
+  CmpNlt = 5,
+  CmpNle = 6,
+  CmpOrd = 7
+}
+
+// xmm = op(xmm, xmm/m)
+fn sse<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                   name: &'static str,
+                                   prefix: Option<u8>,
+                                   op: u8,
+                                   dst: Operand,
+                                   src: Operand) -> AsmResult {
+  match (dst, src) {
+    (D(_), _) if src.is_dm() => {
+      m.emit_sse(prefix, None, op, dst, src, Empty);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+// Load is op, store is op + 1
+fn sse_mov<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                       name: &'static str,
+                                       prefix: Option<u8>,
+                                       op: u8,
+                                       dst: Operand,
+                                       src: Operand) -> AsmResult {
+  match (dst, src) {
+    (D(_), _) if src.is_dm() => {
+      m.emit_sse(prefix, None, op, dst, src, Empty);
+    },
+    (_, D(_)) if dst.is_dm() => {
+      m.emit_sse(prefix, None, op + 1, src, dst, Empty);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+// xmm = op(xmm, xmm/m, imm8)
+fn sse_imm<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                       name: &'static str,
+                                       prefix: Option<u8>,
+                                       op: u8,
+                                       dst: Operand,
+                                       src: Operand,
+                                       imm: Operand) -> AsmResult {
+  match (dst, src, imm) {
+    (D(_), _, Byte(_)) if src.is_dm() => {
+      m.emit_sse(prefix, None, op, dst, src, imm);
+    },
+    _ => return invalid(name, &[dst, src, imm], "unsupported operands")
+  }
+  Ok(())
+}
+
 pub trait AsmIA32FP {
   // Regular
   fn movsd(&mut self, dst: Operand, src: Operand);
@@ -16,6 +80,16 @@ pub trait AsmIA32FP {
   fn subsd(&mut self, dst: Operand, src: Operand);
   fn mulsd(&mut self, dst: Operand, src: Operand);
   fn divsd(&mut self, dst: Operand, src: Operand);
+  fn sqrtsd(&mut self, dst: Operand, src: Operand);
+  fn minsd(&mut self, dst: Operand, src: Operand);
+  fn maxsd(&mut self, dst: Operand, src: Operand);
+  fn cmpsd(&mut self, dst: Operand, src: Operand, pred: CmpPredicate);
+  fn movss(&mut self, dst: Operand, src: Operand);
+  fn addss(&mut self, dst: Operand, src: Operand);
+  fn subss(&mut self, dst: Operand, src: Operand);
+  fn mulss(&mut self, dst: Operand, src: Operand);
+  fn divss(&mut self, dst: Operand, src: Operand);
+  fn sqrtss(&mut self, dst: Operand, src: Operand);
 
   // Binary
   fn andpd(&mut self, dst: Operand, src: Operand);
@@ -26,193 +100,630 @@ pub trait AsmIA32FP {
   fn cvtsi2sd(&mut self, dst: Operand, src: Operand);
   fn cvtsd2si(&mut self, dst: Operand, src: Operand);
   fn cvttsd2si(&mut self, dst: Operand, src: Operand);
+  fn cvtsi2ss(&mut self, dst: Operand, src: Operand);
+  fn cvtss2si(&mut self, dst: Operand, src: Operand);
+  fn cvttss2si(&mut self, dst: Operand, src: Operand);
+  fn cvtss2sd(&mut self, dst: Operand, src: Operand);
+  fn cvtsd2ss(&mut self, dst: Operand, src: Operand);
   fn roundsd(&mut self, dst: Operand, src: Operand, mode: RoundMode);
 
   // Branching
   fn ucomisd(&mut self, dst: Operand, src: Operand);
+  fn ucomiss(&mut self, dst: Operand, src: Operand);
+
+  // Packed
+  fn movaps(&mut self, dst: Operand, src: Operand);
+  fn movups(&mut self, dst: Operand, src: Operand);
+  fn movapd(&mut self, dst: Operand, src: Operand);
+  fn movupd(&mut self, dst: Operand, src: Operand);
+  fn addps(&mut self, dst: Operand, src: Operand);
+  fn addpd(&mut self, dst: Operand, src: Operand);
+  fn subps(&mut self, dst: Operand, src: Operand);
+  fn subpd(&mut self, dst: Operand, src: Operand);
+  fn mulps(&mut self, dst: Operand, src: Operand);
+  fn mulpd(&mut self, dst: Operand, src: Operand);
+  fn divps(&mut self, dst: Operand, src: Operand);
+  fn divpd(&mut self, dst: Operand, src: Operand);
+  fn minps(&mut self, dst: Operand, src: Operand);
+  fn minpd(&mut self, dst: Operand, src: Operand);
+  fn maxps(&mut self, dst: Operand, src: Operand);
+  fn maxpd(&mut self, dst: Operand, src: Operand);
+  fn sqrtps(&mut self, dst: Operand, src: Operand);
+  fn sqrtpd(&mut self, dst: Operand, src: Operand);
+  fn shufps(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn shufpd(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn unpcklpd(&mut self, dst: Operand, src: Operand);
+  fn unpckhpd(&mut self, dst: Operand, src: Operand);
+  fn movhlps(&mut self, dst: Operand, src: Operand);
+  fn movlhps(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmIA32Helper> AsmIA32FP for A {
-  fn movsd(&mut self, dst: Operand, src: Operand) {
+pub trait AsmIA32FPTry {
+  // Regular
+  fn try_movsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movqd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_mulsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sqrtsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_minsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_maxsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpsd(&mut self,
+               dst: Operand,
+               src: Operand,
+               pred: CmpPredicate) -> AsmResult;
+  fn try_movss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_mulss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sqrtss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Binary
+  fn try_andpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Conversion
+  fn try_cvtsi2sd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvtsd2si(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvttsd2si(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvtsi2ss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvtss2si(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvttss2si(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvtss2sd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cvtsd2ss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_roundsd(&mut self,
+                 dst: Operand,
+                 src: Operand,
+                 mode: RoundMode) -> AsmResult;
+
+  // Branching
+  fn try_ucomisd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_ucomiss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Packed
+  fn try_movaps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movups(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movapd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movupd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_mulps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_mulpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_minps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_minpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_maxps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_maxpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sqrtps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sqrtpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shufps(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_shufpd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_unpcklpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_unpckhpd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movhlps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movlhps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmIA32Helper> AsmIA32FPTry for A {
+  fn try_movsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse_mov(self, "movsd", Some(0xf2), 0x10, dst, src)
+  }
+
+  fn try_movqd(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (D(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x10);
-        self.emit_modrm(dst, src);
+        self.emit_sse(Some(0xf3), None, 0x7e, dst, src, Empty);
       },
       (_, D(_)) if dst.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x11);
-        self.emit_modrm(src, dst);
+        self.emit_sse(Some(0x66), None, 0xd6, src, dst, Empty);
       },
-      _ => fail!()
+      _ => return invalid("movqd", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movqd(&mut self, dst: Operand, src: Operand) {
+  fn try_addsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "addsd", Some(0xf2), 0x58, dst, src)
+  }
+
+  fn try_subsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "subsd", Some(0xf2), 0x5c, dst, src)
+  }
+
+  fn try_mulsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "mulsd", Some(0xf2), 0x59, dst, src)
+  }
+
+  fn try_divsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "divsd", Some(0xf2), 0x5e, dst, src)
+  }
+
+  fn try_sqrtsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "sqrtsd", Some(0xf2), 0x51, dst, src)
+  }
+
+  fn try_minsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "minsd", Some(0xf2), 0x5d, dst, src)
+  }
+
+  fn try_maxsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "maxsd", Some(0xf2), 0x5f, dst, src)
+  }
+
+  fn try_cmpsd(&mut self,
+               dst: Operand,
+               src: Operand,
+               pred: CmpPredicate) -> AsmResult {
+    sse_imm(self, "cmpsd", Some(0xf2), 0xc2, dst, src, Byte(pred as u8))
+  }
+
+  fn try_movss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse_mov(self, "movss", Some(0xf3), 0x10, dst, src)
+  }
+
+  fn try_addss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "addss", Some(0xf3), 0x58, dst, src)
+  }
+
+  fn try_subss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "subss", Some(0xf3), 0x5c, dst, src)
+  }
+
+  fn try_mulss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "mulss", Some(0xf3), 0x59, dst, src)
+  }
+
+  fn try_divss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "divss", Some(0xf3), 0x5e, dst, src)
+  }
+
+  fn try_sqrtss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "sqrtss", Some(0xf3), 0x51, dst, src)
+  }
+
+  fn try_andpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "andpd", Some(0x66), 0x54, dst, src)
+  }
+
+  fn try_orpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "orpd", Some(0x66), 0x56, dst, src)
+  }
+
+  fn try_xorpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "xorpd", Some(0x66), 0x57, dst, src)
+  }
+
+  fn try_cvtsi2sd(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0xf3);
-        self.emitb(0x0f);
-        self.emitb(0x7e);
-        self.emit_modrm(dst, src);
-      },
-      (_, D(_)) if dst.is_dm() => {
-        self.emitb(0x66);
-        self.emitb(0x0f);
-        self.emitb(0xd6);
-        self.emit_modrm(src, dst);
+      (D(_), _) if src.is_rm() => {
+        self.emit_sse(Some(0xf2), None, 0x2a, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("cvtsi2sd", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn addsd(&mut self, dst: Operand, src: Operand) {
+  fn try_cvtsd2si(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x58);
-        self.emit_modrm(dst, src);
+      (R(_), _) if src.is_dm() => {
+        self.emit_sse(Some(0xf2), None, 0x2d, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("cvtsd2si", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn subsd(&mut self, dst: Operand, src: Operand) {
+  fn try_cvttsd2si(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x05);
-        self.emitb(0xfc);
-        self.emit_modrm(dst, src);
+      (R(_), _) if src.is_dm() => {
+        self.emit_sse(Some(0xf2), None, 0x2c, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("cvttsd2si", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn mulsd(&mut self, dst: Operand, src: Operand) {
+  fn try_cvtsi2ss(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x59);
-        self.emit_modrm(dst, src);
+      (D(_), _) if src.is_rm() => {
+        self.emit_sse(Some(0xf3), None, 0x2a, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("cvtsi2ss", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn divsd(&mut self, dst: Operand, src: Operand) {
+  fn try_cvtss2si(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x5e);
-        self.emit_modrm(dst, src);
+      (R(_), _) if src.is_dm() => {
+        self.emit_sse(Some(0xf3), None, 0x2d, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("cvtss2si", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn andpd(&mut self, dst: Operand, src: Operand) {
+  fn try_cvttss2si(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0x66);
-        self.emitb(0x0f);
-        self.emitb(0x54);
-        self.emit_modrm(dst, src);
+      (R(_), _) if src.is_dm() => {
+        self.emit_sse(Some(0xf3), None, 0x2c, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("cvttss2si", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn orpd(&mut self, dst: Operand, src: Operand) {
+  fn try_cvtss2sd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "cvtss2sd", Some(0xf3), 0x5a, dst, src)
+  }
+
+  fn try_cvtsd2ss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "cvtsd2ss", Some(0xf2), 0x5a, dst, src)
+  }
+
+  fn try_roundsd(&mut self,
+                 dst: Operand,
+                 src: Operand,
+                 mode: RoundMode) -> AsmResult {
     match (dst, src) {
       (D(_), _) if src.is_dm() => {
-        self.emitb(0x66);
-        self.emitb(0x0f);
-        self.emitb(0x56);
-        self.emit_modrm(dst, src);
+        self.emit_sse(Some(0x66), Some(0x3a), 0x0b, dst, src,
+                      Byte(0b1000 | (mode as u8)));
       },
-      _ => fail!()
+      _ => return invalid("roundsd", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn xorpd(&mut self, dst: Operand, src: Operand) {
+  fn try_ucomisd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "ucomisd", Some(0x66), 0x2e, dst, src)
+  }
+
+  fn try_ucomiss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "ucomiss", None, 0x2e, dst, src)
+  }
+
+  fn try_movaps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse_mov(self, "movaps", None, 0x28, dst, src)
+  }
+
+  fn try_movups(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse_mov(self, "movups", None, 0x10, dst, src)
+  }
+
+  fn try_movapd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse_mov(self, "movapd", Some(0x66), 0x28, dst, src)
+  }
+
+  fn try_movupd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse_mov(self, "movupd", Some(0x66), 0x10, dst, src)
+  }
+
+  fn try_addps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "addps", None, 0x58, dst, src)
+  }
+
+  fn try_addpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "addpd", Some(0x66), 0x58, dst, src)
+  }
+
+  fn try_subps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "subps", None, 0x5c, dst, src)
+  }
+
+  fn try_subpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "subpd", Some(0x66), 0x5c, dst, src)
+  }
+
+  fn try_mulps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "mulps", None, 0x59, dst, src)
+  }
+
+  fn try_mulpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "mulpd", Some(0x66), 0x59, dst, src)
+  }
+
+  fn try_divps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "divps", None, 0x5e, dst, src)
+  }
+
+  fn try_divpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "divpd", Some(0x66), 0x5e, dst, src)
+  }
+
+  fn try_minps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "minps", None, 0x5d, dst, src)
+  }
+
+  fn try_minpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "minpd", Some(0x66), 0x5d, dst, src)
+  }
+
+  fn try_maxps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "maxps", None, 0x5f, dst, src)
+  }
+
+  fn try_maxpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "maxpd", Some(0x66), 0x5f, dst, src)
+  }
+
+  fn try_sqrtps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "sqrtps", None, 0x51, dst, src)
+  }
+
+  fn try_sqrtpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "sqrtpd", Some(0x66), 0x51, dst, src)
+  }
+
+  fn try_shufps(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    sse_imm(self, "shufps", None, 0xc6, dst, src, imm)
+  }
+
+  fn try_shufpd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    sse_imm(self, "shufpd", Some(0x66), 0xc6, dst, src, imm)
+  }
+
+  fn try_unpcklpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "unpcklpd", Some(0x66), 0x14, dst, src)
+  }
+
+  fn try_unpckhpd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    sse(self, "unpckhpd", Some(0x66), 0x15, dst, src)
+  }
+
+  fn try_movhlps(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0x66);
-        self.emitb(0x0f);
-        self.emitb(0x57);
-        self.emit_modrm(dst, src);
+      (D(_), D(_)) => {
+        self.emit_sse(None, None, 0x12, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("movhlps", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn cvtsi2sd(&mut self, dst: Operand, src: Operand) {
+  fn try_movlhps(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (D(_), _) if src.is_rm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x2a);
-        self.emit_modrm(dst, src);
+      (D(_), D(_)) => {
+        self.emit_sse(None, None, 0x16, dst, src, Empty);
       },
-      _ => fail!()
+      _ => return invalid("movlhps", &[dst, src], "unsupported operands")
     }
+    Ok(())
+  }
+}
+
+impl<A: AsmBuffer+AsmIA32Helper+AsmIA32FPTry> AsmIA32FP for A {
+  fn movsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movsd(dst, src));
+  }
+
+  fn movqd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movqd(dst, src));
+  }
+
+  fn addsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addsd(dst, src));
+  }
+
+  fn subsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subsd(dst, src));
+  }
+
+  fn mulsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_mulsd(dst, src));
+  }
+
+  fn divsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_divsd(dst, src));
+  }
+
+  fn sqrtsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sqrtsd(dst, src));
+  }
+
+  fn minsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_minsd(dst, src));
+  }
+
+  fn maxsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_maxsd(dst, src));
+  }
+
+  fn cmpsd(&mut self, dst: Operand, src: Operand, pred: CmpPredicate) {
+    or_fail(self.try_cmpsd(dst, src, pred));
+  }
+
+  fn movss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movss(dst, src));
+  }
+
+  fn addss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addss(dst, src));
+  }
+
+  fn subss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subss(dst, src));
+  }
+
+  fn mulss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_mulss(dst, src));
+  }
+
+  fn divss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_divss(dst, src));
+  }
+
+  fn sqrtss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sqrtss(dst, src));
+  }
+
+  fn andpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_andpd(dst, src));
+  }
+
+  fn orpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_orpd(dst, src));
+  }
+
+  fn xorpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xorpd(dst, src));
+  }
+
+  fn cvtsi2sd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cvtsi2sd(dst, src));
   }
 
   fn cvtsd2si(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x2d);
-        self.emit_modrm(dst, src);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_cvtsd2si(dst, src));
   }
 
   fn cvttsd2si(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(_), _) if src.is_dm() => {
-        self.emitb(0xf2);
-        self.emitb(0x0f);
-        self.emitb(0x2c);
-        self.emit_modrm(dst, src);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_cvttsd2si(dst, src));
   }
 
-  fn roundsd(&mut self, dst: Operand, src: Operand, mode: RoundMode) {
-    match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0x66);
-        self.emitb(0x0f);
-        self.emitb(0x3a);
-        self.emitb(0x0b);
-        self.emit_modrm(dst, src);
+  fn cvtsi2ss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cvtsi2ss(dst, src));
+  }
 
-        self.emitb(0b1000 | (mode as u8));
-      },
-      _ => fail!()
-    }
+  fn cvtss2si(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cvtss2si(dst, src));
+  }
+
+  fn cvttss2si(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cvttss2si(dst, src));
+  }
+
+  fn cvtss2sd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cvtss2sd(dst, src));
+  }
+
+  fn cvtsd2ss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cvtsd2ss(dst, src));
+  }
+
+  fn roundsd(&mut self, dst: Operand, src: Operand, mode: RoundMode) {
+    or_fail(self.try_roundsd(dst, src, mode));
   }
 
   fn ucomisd(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (D(_), _) if src.is_dm() => {
-        self.emitb(0x66);
-        self.emitb(0x0f);
-        self.emitb(0x2e);
-        self.emit_modrm(dst, src);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_ucomisd(dst, src));
+  }
+
+  fn ucomiss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_ucomiss(dst, src));
+  }
+
+  fn movaps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movaps(dst, src));
+  }
+
+  fn movups(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movups(dst, src));
+  }
+
+  fn movapd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movapd(dst, src));
+  }
+
+  fn movupd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movupd(dst, src));
+  }
+
+  fn addps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addps(dst, src));
+  }
+
+  fn addpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addpd(dst, src));
+  }
+
+  fn subps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subps(dst, src));
+  }
+
+  fn subpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subpd(dst, src));
+  }
+
+  fn mulps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_mulps(dst, src));
+  }
+
+  fn mulpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_mulpd(dst, src));
+  }
+
+  fn divps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_divps(dst, src));
+  }
+
+  fn divpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_divpd(dst, src));
+  }
+
+  fn minps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_minps(dst, src));
+  }
+
+  fn minpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_minpd(dst, src));
+  }
+
+  fn maxps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_maxps(dst, src));
+  }
+
+  fn maxpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_maxpd(dst, src));
+  }
+
+  fn sqrtps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sqrtps(dst, src));
+  }
+
+  fn sqrtpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sqrtpd(dst, src));
+  }
+
+  fn shufps(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_shufps(dst, src, imm));
+  }
+
+  fn shufpd(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_shufpd(dst, src, imm));
+  }
+
+  fn unpcklpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_unpcklpd(dst, src));
+  }
+
+  fn unpckhpd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_unpckhpd(dst, src));
+  }
+
+  fn movhlps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movhlps(dst, src));
+  }
+
+  fn movlhps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movlhps(dst, src));
   }
 }
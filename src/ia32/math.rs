@@ -1,6 +1,94 @@
 use asm::*;
 use asm::ia32::base::*;
 
+fn unary<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                     name: &'static str,
+                                     op: u8,
+                                     digit: u8,
+                                     size: OperandSize,
+                                     dst: Operand) -> AsmResult {
+  if !dst.is_rm() {
+    return invalid(name, &[dst], "expected register or memory");
+  }
+  match size {
+    Size8 if !dst.has_byte_form() => {
+      return invalid(name, &[dst], "no byte form of the register");
+    },
+    _ => ()
+  }
+  m.emit_size_prefix(size);
+  m.emitb(size.opcode(op));
+  m.emit_modrm(_Operation(digit), dst);
+  Ok(())
+}
+
+fn shift<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                     name: &'static str,
+                                     digit: u8,
+                                     dst: Operand,
+                                     src: Operand) -> AsmResult {
+  match (dst, src) {
+    (_, Byte(1)) if dst.is_rm() => {
+      m.emitb(0xd1);
+      m.emit_modrm(_Operation(digit), dst);
+    },
+    (_, Byte(b)) if dst.is_rm() => {
+      m.emitb(0xc1);
+      m.emit_modrm(_Operation(digit), dst);
+      m.emitb(b);
+    },
+    (_, R(ecx)) if dst.is_rm() => {
+      m.emitb(0xd3);
+      m.emit_modrm(_Operation(digit), dst);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+fn bit_test<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                        name: &'static str,
+                                        digit: u8,
+                                        dst: Operand,
+                                        src: Operand) -> AsmResult {
+  match (dst, src) {
+    (_, R(_)) if dst.is_rm() => {
+      m.emitb(0x0f);
+      m.emitb(0xa3 | ((digit - 4) << 3));
+      m.emit_modrm(src, dst);
+    },
+    (_, Byte(b)) if dst.is_rm() => {
+      m.emitb(0x0f);
+      m.emitb(0xba);
+      m.emit_modrm(_Operation(digit), dst);
+      m.emitb(b);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+fn bit_scan<A: AsmBuffer+AsmIA32Helper>(m: &mut A,
+                                        name: &'static str,
+                                        prefix: Option<u8>,
+                                        op: u8,
+                                        dst: Operand,
+                                        src: Operand) -> AsmResult {
+  match (dst, src) {
+    (R(_), _) if src.is_rm() => {
+      match prefix {
+        Some(p) => m.emitb(p),
+        None => ()
+      }
+      m.emitb(0x0f);
+      m.emitb(op);
+      m.emit_modrm(dst, src);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
 pub trait AsmIA32Math {
   fn incl(&mut self, dst: Operand);
   fn decl(&mut self, dst: Operand);
@@ -16,216 +104,616 @@ pub trait AsmIA32Math {
   fn andl(&mut self, dst: Operand, src: Operand);
   fn orl(&mut self, dst: Operand, src: Operand);
   fn xorl(&mut self, dst: Operand, src: Operand);
+  fn roll(&mut self, dst: Operand, src: Operand);
+  fn rorl(&mut self, dst: Operand, src: Operand);
+  fn rcll(&mut self, dst: Operand, src: Operand);
+  fn rcrl(&mut self, dst: Operand, src: Operand);
+  fn imull2(&mut self, dst: Operand, src: Operand);
+  fn imull3(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn cdq(&mut self);
+  fn cwd(&mut self);
+  fn cwde(&mut self);
+  fn cbw(&mut self);
+  fn adcl(&mut self, dst: Operand, src: Operand);
+  fn sbbl(&mut self, dst: Operand, src: Operand);
+  fn negl(&mut self, dst: Operand);
+  fn notl(&mut self, dst: Operand);
+  fn negw(&mut self, dst: Operand);
+  fn negb(&mut self, dst: Operand);
+  fn notw(&mut self, dst: Operand);
+  fn notb(&mut self, dst: Operand);
+  fn incw(&mut self, dst: Operand);
+  fn incb(&mut self, dst: Operand);
+  fn decw(&mut self, dst: Operand);
+  fn decb(&mut self, dst: Operand);
+  fn addw(&mut self, dst: Operand, src: Operand);
+  fn addb(&mut self, dst: Operand, src: Operand);
+  fn subw(&mut self, dst: Operand, src: Operand);
+  fn subb(&mut self, dst: Operand, src: Operand);
+  fn andw(&mut self, dst: Operand, src: Operand);
+  fn andb(&mut self, dst: Operand, src: Operand);
+  fn orw(&mut self, dst: Operand, src: Operand);
+  fn orb(&mut self, dst: Operand, src: Operand);
+  fn xorw(&mut self, dst: Operand, src: Operand);
+  fn xorb(&mut self, dst: Operand, src: Operand);
+  fn adcw(&mut self, dst: Operand, src: Operand);
+  fn adcb(&mut self, dst: Operand, src: Operand);
+  fn sbbw(&mut self, dst: Operand, src: Operand);
+  fn sbbb(&mut self, dst: Operand, src: Operand);
+  fn btl(&mut self, dst: Operand, src: Operand);
+  fn btsl(&mut self, dst: Operand, src: Operand);
+  fn btrl(&mut self, dst: Operand, src: Operand);
+  fn btcl(&mut self, dst: Operand, src: Operand);
+  fn bsfl(&mut self, dst: Operand, src: Operand);
+  fn bsrl(&mut self, dst: Operand, src: Operand);
+  fn popcntl(&mut self, dst: Operand, src: Operand);
+  fn lzcntl(&mut self, dst: Operand, src: Operand);
+  fn tzcntl(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmIA32Helper> AsmIA32Math for A {
-  fn incl(&mut self, dst: Operand) {
-    self.emitb(0xff);
-    self.emit_modrm(_Operation(0), dst);
+pub trait AsmIA32MathTry {
+  fn try_incl(&mut self, dst: Operand) -> AsmResult;
+  fn try_decl(&mut self, dst: Operand) -> AsmResult;
+  fn try_addl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divl(&mut self, src: Operand) -> AsmResult;
+  fn try_mull(&mut self, src: Operand) -> AsmResult;
+  fn try_idivl(&mut self, src: Operand) -> AsmResult;
+  fn try_imull(&mut self, src: Operand) -> AsmResult;
+  fn try_shll(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shrl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sarl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_roll(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rorl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcll(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcrl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_imull2(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_imull3(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_adcl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_negl(&mut self, dst: Operand) -> AsmResult;
+  fn try_notl(&mut self, dst: Operand) -> AsmResult;
+  fn try_negw(&mut self, dst: Operand) -> AsmResult;
+  fn try_negb(&mut self, dst: Operand) -> AsmResult;
+  fn try_notw(&mut self, dst: Operand) -> AsmResult;
+  fn try_notb(&mut self, dst: Operand) -> AsmResult;
+  fn try_incw(&mut self, dst: Operand) -> AsmResult;
+  fn try_incb(&mut self, dst: Operand) -> AsmResult;
+  fn try_decw(&mut self, dst: Operand) -> AsmResult;
+  fn try_decb(&mut self, dst: Operand) -> AsmResult;
+  fn try_addw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_adcw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_adcb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btsl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btrl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btcl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_bsfl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_bsrl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_popcntl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_lzcntl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_tzcntl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmIA32Helper> AsmIA32MathTry for A {
+  fn try_incl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incl", 0xfe, 0, Size32, dst)
   }
 
-  fn decl(&mut self, dst: Operand) {
-    self.emitb(0xff);
-    self.emit_modrm(_Operation(1), dst);
+  fn try_decl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decl", 0xfe, 1, Size32, dst)
   }
 
-  fn addl(&mut self, dst: Operand, src: Operand) {
+  fn try_addl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size32, dst, src)
+  }
+
+  fn try_subl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size32, dst, src)
+  }
+
+  fn try_divl(&mut self, src: Operand) -> AsmResult {
+    if !src.is_rm() {
+      return invalid("divl", &[src], "expected register or memory");
+    }
+    self.emitb(0xf7);
+    self.emit_modrm(_Operation(6), src);
+    Ok(())
+  }
+
+  fn try_mull(&mut self, src: Operand) -> AsmResult {
+    if !src.is_rm() {
+      return invalid("mull", &[src], "expected register or memory");
+    }
+    self.emitb(0xf7);
+    self.emit_modrm(_Operation(4), src);
+    Ok(())
+  }
+
+  fn try_idivl(&mut self, src: Operand) -> AsmResult {
+    if !src.is_rm() {
+      return invalid("idivl", &[src], "expected register or memory");
+    }
+    self.emitb(0xf7);
+    self.emit_modrm(_Operation(7), src);
+    Ok(())
+  }
+
+  fn try_imull(&mut self, src: Operand) -> AsmResult {
+    if !src.is_rm() {
+      return invalid("imull", &[src], "expected register or memory");
+    }
+    self.emitb(0xf7);
+    self.emit_modrm(_Operation(5), src);
+    Ok(())
+  }
+
+  fn try_shll(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shll", 4, dst, src)
+  }
+
+  fn try_shrl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shrl", 5, dst, src)
+  }
+
+  fn try_sarl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "sarl", 7, dst, src)
+  }
+
+  fn try_andl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size32, dst, src)
+  }
+
+  fn try_orl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size32, dst, src)
+  }
+
+  fn try_xorl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size32, dst, src)
+  }
+
+  fn try_roll(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "roll", 0, dst, src)
+  }
+
+  fn try_rorl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rorl", 1, dst, src)
+  }
+
+  fn try_rcll(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcll", 2, dst, src)
+  }
+
+  fn try_rcrl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcrl", 3, dst, src)
+  }
+
+  fn try_imull2(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (R(eax), Long(l)) => {
-        self.emitb(0x05);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(0), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(0), dst);
-        self.emitl(l);
-      },
       (R(_), _) if src.is_rm() => {
-        self.emitb(0x03);
+        self.emitb(0x0f);
+        self.emitb(0xaf);
         self.emit_modrm(dst, src);
       },
-      (_, R(_)) if dst.is_rm() => {
-        self.emitb(0x01);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
+      _ => return invalid("imull2", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn subl(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(eax), Long(l)) => {
-        self.emitb(0x2d);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(5), dst);
+  fn try_imull3(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (R(_), _, Byte(b)) if src.is_rm() => {
+        self.emitb(0x6b);
+        self.emit_modrm(dst, src);
         self.emitb(b);
       },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(5), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emitb(0x2b);
+      (R(_), _, Long(l)) if src.is_rm() => {
+        self.emitb(0x69);
         self.emit_modrm(dst, src);
+        self.emitl(l);
       },
-      (_, R(_)) if dst.is_rm() => {
-        self.emitb(0x29);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
+      _ => return invalid("imull3", &[dst, src, imm], "unsupported operands")
     }
+    Ok(())
+  }
+
+  fn try_adcl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size32, dst, src)
+  }
+
+  fn try_sbbl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size32, dst, src)
+  }
+
+  fn try_negl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negl", 0xf6, 3, Size32, dst)
+  }
+
+  fn try_notl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notl", 0xf6, 2, Size32, dst)
+  }
+
+  fn try_negw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negw", 0xf6, 3, Size16, dst)
+  }
+
+  fn try_negb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negb", 0xf6, 3, Size8, dst)
+  }
+
+  fn try_notw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notw", 0xf6, 2, Size16, dst)
+  }
+
+  fn try_notb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notb", 0xf6, 2, Size8, dst)
+  }
+
+  fn try_incw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incw", 0xfe, 0, Size16, dst)
+  }
+
+  fn try_incb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incb", 0xfe, 0, Size8, dst)
+  }
+
+  fn try_decw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decw", 0xfe, 1, Size16, dst)
+  }
+
+  fn try_decb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decb", 0xfe, 1, Size8, dst)
+  }
+
+  fn try_addw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size16, dst, src)
+  }
+
+  fn try_addb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size8, dst, src)
+  }
+
+  fn try_subw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size16, dst, src)
+  }
+
+  fn try_subb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size8, dst, src)
+  }
+
+  fn try_andw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size16, dst, src)
+  }
+
+  fn try_andb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size8, dst, src)
+  }
+
+  fn try_orw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size16, dst, src)
+  }
+
+  fn try_orb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size8, dst, src)
+  }
+
+  fn try_xorw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size16, dst, src)
+  }
+
+  fn try_xorb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size8, dst, src)
+  }
+
+  fn try_adcw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size16, dst, src)
+  }
+
+  fn try_adcb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size8, dst, src)
+  }
+
+  fn try_sbbw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size16, dst, src)
+  }
+
+  fn try_sbbb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size8, dst, src)
+  }
+
+  fn try_btl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btl", 4, dst, src)
+  }
+
+  fn try_btsl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btsl", 5, dst, src)
+  }
+
+  fn try_btrl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btrl", 6, dst, src)
+  }
+
+  fn try_btcl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btcl", 7, dst, src)
+  }
+
+  fn try_bsfl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "bsfl", None, 0xbc, dst, src)
+  }
+
+  fn try_bsrl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "bsrl", None, 0xbd, dst, src)
+  }
+
+  fn try_popcntl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "popcntl", Some(0xf3), 0xb8, dst, src)
+  }
+
+  fn try_lzcntl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "lzcntl", Some(0xf3), 0xbd, dst, src)
+  }
+
+  fn try_tzcntl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "tzcntl", Some(0xf3), 0xbc, dst, src)
+  }
+}
+
+impl<A: AsmBuffer+AsmIA32Helper+AsmIA32MathTry> AsmIA32Math for A {
+  fn incl(&mut self, dst: Operand) {
+    or_fail(self.try_incl(dst));
+  }
+
+  fn decl(&mut self, dst: Operand) {
+    or_fail(self.try_decl(dst));
+  }
+
+  fn addl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addl(dst, src));
+  }
+
+  fn subl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subl(dst, src));
   }
 
   fn divl(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(6), src);
+    or_fail(self.try_divl(src));
   }
 
   fn mull(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(4), src);
+    or_fail(self.try_mull(src));
   }
 
   fn idivl(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(7), src);
+    or_fail(self.try_idivl(src));
   }
 
   fn imull(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(5), src);
+    or_fail(self.try_imull(src));
   }
 
   fn shll(&mut self, dst: Operand, src: Operand) {
-    assert!(dst.is_rm());
-    match src {
-      Byte(b) => {
-        self.emitb(0xc1);
-        self.emit_modrm(_Operation(4), dst);
-        self.emitb(b);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_shll(dst, src));
   }
 
   fn shrl(&mut self, dst: Operand, src: Operand) {
-    assert!(dst.is_rm());
-    match src {
-      Byte(b) => {
-        self.emitb(0xc1);
-        self.emit_modrm(_Operation(5), dst);
-        self.emitb(b);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_shrl(dst, src));
   }
 
   fn sarl(&mut self, dst: Operand, src: Operand) {
-    assert!(dst.is_rm());
-    match src {
-      Byte(b) => {
-        self.emitb(0xc1);
-        self.emit_modrm(_Operation(7), dst);
-        self.emitb(b);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_sarl(dst, src));
   }
 
   fn andl(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(eax), Long(l)) => {
-        self.emitb(0x25);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(4), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(4), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emitb(0x23);
-        self.emit_modrm(dst, src);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emitb(0x21);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_andl(dst, src));
   }
 
   fn orl(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(eax), Long(l)) => {
-        self.emitb(0x0d);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(1), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(1), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emitb(0x0b);
-        self.emit_modrm(dst, src);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emitb(0x0a);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_orl(dst, src));
   }
 
   fn xorl(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(eax), Long(l)) => {
-        self.emitb(0x35);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(6), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(6), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emitb(0x33);
-        self.emit_modrm(dst, src);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emitb(0x31);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_xorl(dst, src));
+  }
+
+  fn roll(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_roll(dst, src));
+  }
+
+  fn rorl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rorl(dst, src));
+  }
+
+  fn rcll(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcll(dst, src));
+  }
+
+  fn rcrl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcrl(dst, src));
+  }
+
+  fn imull2(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_imull2(dst, src));
+  }
+
+  fn imull3(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_imull3(dst, src, imm));
+  }
+
+  fn cdq(&mut self) {
+    self.emitb(0x99);
+  }
+
+  fn cwd(&mut self) {
+    self.emitb(0x66);
+    self.emitb(0x99);
+  }
+
+  fn cwde(&mut self) {
+    self.emitb(0x98);
+  }
+
+  fn cbw(&mut self) {
+    self.emitb(0x66);
+    self.emitb(0x98);
+  }
+
+  fn adcl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcl(dst, src));
+  }
+
+  fn sbbl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbl(dst, src));
+  }
+
+  fn negl(&mut self, dst: Operand) {
+    or_fail(self.try_negl(dst));
+  }
+
+  fn notl(&mut self, dst: Operand) {
+    or_fail(self.try_notl(dst));
+  }
+
+  fn negw(&mut self, dst: Operand) {
+    or_fail(self.try_negw(dst));
+  }
+
+  fn negb(&mut self, dst: Operand) {
+    or_fail(self.try_negb(dst));
+  }
+
+  fn notw(&mut self, dst: Operand) {
+    or_fail(self.try_notw(dst));
+  }
+
+  fn notb(&mut self, dst: Operand) {
+    or_fail(self.try_notb(dst));
+  }
+
+  fn incw(&mut self, dst: Operand) {
+    or_fail(self.try_incw(dst));
+  }
+
+  fn incb(&mut self, dst: Operand) {
+    or_fail(self.try_incb(dst));
+  }
+
+  fn decw(&mut self, dst: Operand) {
+    or_fail(self.try_decw(dst));
+  }
+
+  fn decb(&mut self, dst: Operand) {
+    or_fail(self.try_decb(dst));
+  }
+
+  fn addw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addw(dst, src));
+  }
+
+  fn addb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addb(dst, src));
+  }
+
+  fn subw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subw(dst, src));
+  }
+
+  fn subb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subb(dst, src));
+  }
+
+  fn andw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_andw(dst, src));
+  }
+
+  fn andb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_andb(dst, src));
+  }
+
+  fn orw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_orw(dst, src));
+  }
+
+  fn orb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_orb(dst, src));
+  }
+
+  fn xorw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xorw(dst, src));
+  }
+
+  fn xorb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xorb(dst, src));
+  }
+
+  fn adcw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcw(dst, src));
+  }
+
+  fn adcb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcb(dst, src));
+  }
+
+  fn sbbw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbw(dst, src));
+  }
+
+  fn sbbb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbb(dst, src));
+  }
+
+  fn btl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btl(dst, src));
+  }
+
+  fn btsl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btsl(dst, src));
+  }
+
+  fn btrl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btrl(dst, src));
+  }
+
+  fn btcl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btcl(dst, src));
+  }
+
+  fn bsfl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_bsfl(dst, src));
+  }
+
+  fn bsrl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_bsrl(dst, src));
+  }
+
+  fn popcntl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_popcntl(dst, src));
+  }
+
+  fn lzcntl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_lzcntl(dst, src));
+  }
+
+  fn tzcntl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_tzcntl(dst, src));
   }
 }
@@ -0,0 +1,60 @@
+use asm::*;
+use asm::ia32::base::*;
+
+fn string<A: AsmBuffer+AsmIA32Helper>(m: &mut A, size: OperandSize, op: u8) {
+  m.emit_size_prefix(size);
+  m.emitb(size.opcode(op));
+}
+
+pub trait AsmIA32String {
+  // Prefixes
+  fn rep(&mut self);
+  fn repe(&mut self);
+  fn repne(&mut self);
+
+  fn movsb(&mut self);
+  fn movsw(&mut self);
+  fn movsl(&mut self);
+
+  fn stosb(&mut self);
+  fn stosw(&mut self);
+  fn stosl(&mut self);
+
+  fn lodsb(&mut self);
+  fn lodsw(&mut self);
+  fn lodsl(&mut self);
+
+  fn cmpsb(&mut self);
+  fn cmpsw(&mut self);
+  fn cmpsl(&mut self);
+
+  fn scasb(&mut self);
+  fn scasw(&mut self);
+  fn scasl(&mut self);
+}
+
+impl<A: AsmBuffer+AsmIA32Helper> AsmIA32String for A {
+  fn rep(&mut self) { self.emitb(0xf3); }
+  fn repe(&mut self) { self.emitb(0xf3); }
+  fn repne(&mut self) { self.emitb(0xf2); }
+
+  fn movsb(&mut self) { string(self, Size8, 0xa4); }
+  fn movsw(&mut self) { string(self, Size16, 0xa4); }
+  fn movsl(&mut self) { string(self, Size32, 0xa4); }
+
+  fn stosb(&mut self) { string(self, Size8, 0xaa); }
+  fn stosw(&mut self) { string(self, Size16, 0xaa); }
+  fn stosl(&mut self) { string(self, Size32, 0xaa); }
+
+  fn lodsb(&mut self) { string(self, Size8, 0xac); }
+  fn lodsw(&mut self) { string(self, Size16, 0xac); }
+  fn lodsl(&mut self) { string(self, Size32, 0xac); }
+
+  fn cmpsb(&mut self) { string(self, Size8, 0xa6); }
+  fn cmpsw(&mut self) { string(self, Size16, 0xa6); }
+  fn cmpsl(&mut self) { string(self, Size32, 0xa6); }
+
+  fn scasb(&mut self) { string(self, Size8, 0xae); }
+  fn scasw(&mut self) { string(self, Size16, 0xae); }
+  fn scasl(&mut self) { string(self, Size32, 0xae); }
+}
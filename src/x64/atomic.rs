@@ -0,0 +1,179 @@
+use asm::*;
+use asm::x64::base::*;
+
+// xadd and cmpxchg, byte forms use `op` and the others `op + 1`
+fn exchange<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                       name: &'static str,
+                                       op: u8,
+                                       size: OperandSize,
+                                       dst: Operand,
+                                       src: Operand) -> AsmResult {
+  match (dst, src) {
+    (_, R(_)) if dst.is_rm() => {
+      m.emit_sized_rex(size, src, dst);
+      m.emitb(0x0f);
+      m.emitb(size.opcode(op));
+      m.emit_modrm(src, dst);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+pub trait AsmX64Atomic {
+  // Must be followed by a read-modify-write instruction with a memory
+  // destination, such as xadd, cmpxchg or add, anything else is #UD
+  fn lock(&mut self);
+  fn xaddq(&mut self, dst: Operand, src: Operand);
+  fn xaddl(&mut self, dst: Operand, src: Operand);
+  fn xaddw(&mut self, dst: Operand, src: Operand);
+  fn xaddb(&mut self, dst: Operand, src: Operand);
+  fn cmpxchgq(&mut self, dst: Operand, src: Operand);
+  fn cmpxchgl(&mut self, dst: Operand, src: Operand);
+  fn cmpxchgw(&mut self, dst: Operand, src: Operand);
+  fn cmpxchgb(&mut self, dst: Operand, src: Operand);
+  fn cmpxchg8b(&mut self, dst: Operand);
+  fn cmpxchg16b(&mut self, dst: Operand);
+  fn mfence(&mut self);
+  fn lfence(&mut self);
+  fn sfence(&mut self);
+  fn pause(&mut self);
+}
+
+pub trait AsmX64AtomicTry {
+  fn try_xaddq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xaddl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xaddw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xaddb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpxchgq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpxchgl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpxchgw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpxchgb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpxchg8b(&mut self, dst: Operand) -> AsmResult;
+  fn try_cmpxchg16b(&mut self, dst: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64AtomicTry for A {
+  fn try_xaddq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "xaddq", 0xc0, Size64, dst, src)
+  }
+
+  fn try_xaddl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "xaddl", 0xc0, Size32, dst, src)
+  }
+
+  fn try_xaddw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "xaddw", 0xc0, Size16, dst, src)
+  }
+
+  fn try_xaddb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "xaddb", 0xc0, Size8, dst, src)
+  }
+
+  fn try_cmpxchgq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "cmpxchgq", 0xb0, Size64, dst, src)
+  }
+
+  fn try_cmpxchgl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "cmpxchgl", 0xb0, Size32, dst, src)
+  }
+
+  fn try_cmpxchgw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "cmpxchgw", 0xb0, Size16, dst, src)
+  }
+
+  fn try_cmpxchgb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    exchange(self, "cmpxchgb", 0xb0, Size8, dst, src)
+  }
+
+  fn try_cmpxchg8b(&mut self, dst: Operand) -> AsmResult {
+    if !dst.is_mem() {
+      return invalid("cmpxchg8b", &[dst], "expected memory");
+    }
+    self.emit_opt_rex(Empty, dst);
+    self.emitb(0x0f);
+    self.emitb(0xc7);
+    self.emit_modrm(_Operation(1), dst);
+    Ok(())
+  }
+
+  fn try_cmpxchg16b(&mut self, dst: Operand) -> AsmResult {
+    if !dst.is_mem() {
+      return invalid("cmpxchg16b", &[dst], "expected memory");
+    }
+    self.emit_rex(REXW, Empty, dst);
+    self.emitb(0x0f);
+    self.emitb(0xc7);
+    self.emit_modrm(_Operation(1), dst);
+    Ok(())
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64AtomicTry> AsmX64Atomic for A {
+  fn lock(&mut self) {
+    self.emitb(0xf0);
+  }
+
+  fn xaddq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xaddq(dst, src));
+  }
+
+  fn xaddl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xaddl(dst, src));
+  }
+
+  fn xaddw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xaddw(dst, src));
+  }
+
+  fn xaddb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xaddb(dst, src));
+  }
+
+  fn cmpxchgq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpxchgq(dst, src));
+  }
+
+  fn cmpxchgl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpxchgl(dst, src));
+  }
+
+  fn cmpxchgw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpxchgw(dst, src));
+  }
+
+  fn cmpxchgb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpxchgb(dst, src));
+  }
+
+  fn cmpxchg8b(&mut self, dst: Operand) {
+    or_fail(self.try_cmpxchg8b(dst));
+  }
+
+  fn cmpxchg16b(&mut self, dst: Operand) {
+    or_fail(self.try_cmpxchg16b(dst));
+  }
+
+  fn mfence(&mut self) {
+    self.emitb(0x0f);
+    self.emitb(0xae);
+    self.emitb(0xf0);
+  }
+
+  fn lfence(&mut self) {
+    self.emitb(0x0f);
+    self.emitb(0xae);
+    self.emitb(0xe8);
+  }
+
+  fn sfence(&mut self) {
+    self.emitb(0x0f);
+    self.emitb(0xae);
+    self.emitb(0xf8);
+  }
+
+  fn pause(&mut self) {
+    self.emitb(0xf3);
+    self.emitb(0x90);
+  }
+}
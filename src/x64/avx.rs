@@ -0,0 +1,847 @@
+use asm::*;
+use asm::x64::base::*;
+
+// dst = op(src1, src2), src1 goes into VEX.vvvv and the register class
+// of dst selects between 128 and 256 bits
+fn avx<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                  name: &'static str,
+                                  w: bool,
+                                  pp: VexPrefix,
+                                  map: VexMap,
+                                  op: u8,
+                                  dst: Operand,
+                                  src1: Operand,
+                                  src2: Operand) -> AsmResult {
+  match (dst, src1) {
+    (D(_), D(_)) if src2.is_dm() => {
+      m.vex_op(w, false, pp, map, op, dst, src1, src2, Empty);
+    },
+    (Y(_), Y(_)) if src2.is_ym() => {
+      m.vex_op(w, true, pp, map, op, dst, src1, src2, Empty);
+    },
+    _ => return invalid(name, &[dst, src1, src2], "unsupported operands")
+  }
+  Ok(())
+}
+
+fn avx_mov<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                      name: &'static str,
+                                      pp: VexPrefix,
+                                      load: u8,
+                                      store: u8,
+                                      dst: Operand,
+                                      src: Operand) -> AsmResult {
+  match (dst, src) {
+    (D(_), _) if src.is_dm() => {
+      m.vex_op(false, false, pp, Vex0F, load, dst, Empty, src, Empty);
+    },
+    (Y(_), _) if src.is_ym() => {
+      m.vex_op(false, true, pp, Vex0F, load, dst, Empty, src, Empty);
+    },
+    (_, D(_)) if dst.is_mem() => {
+      m.vex_op(false, false, pp, Vex0F, store, src, Empty, dst, Empty);
+    },
+    (_, Y(_)) if dst.is_mem() => {
+      m.vex_op(false, true, pp, Vex0F, store, src, Empty, dst, Empty);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+// The mask is cleared as elements are loaded, so dst, the index and the
+// mask have to be different registers
+fn gather<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                     name: &'static str,
+                                     op: u8,
+                                     dst: Operand,
+                                     src: Operand,
+                                     mask: Operand) -> AsmResult {
+  let (l, index) = match (dst, src, mask) {
+    (D(_), VM(_, i, _, _), D(_)) => (false, i as u8),
+    (Y(_), VM(_, i, _, _), Y(_)) => (true, i as u8),
+    _ => return invalid(name, &[dst, src, mask], "unsupported operands")
+  };
+  if dst.num() == mask.num() || dst.num() == index || mask.num() == index {
+    return invalid(name, &[dst, src, mask], "registers must be distinct");
+  }
+  m.vex_op(false, l, Vex66, Vex0F38, op, dst, mask, src, Empty);
+  Ok(())
+}
+
+pub trait AsmX64Avx {
+  // Moves
+  fn vmovaps(&mut self, dst: Operand, src: Operand);
+  fn vmovups(&mut self, dst: Operand, src: Operand);
+  fn vmovapd(&mut self, dst: Operand, src: Operand);
+  fn vmovupd(&mut self, dst: Operand, src: Operand);
+  fn vmovdqa(&mut self, dst: Operand, src: Operand);
+  fn vmovdqu(&mut self, dst: Operand, src: Operand);
+
+  // Arithmetic
+  fn vaddps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vaddpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vsubps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vsubpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vmulps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vmulpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vdivps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vdivpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vminps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vminpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vmaxps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vmaxpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+
+  // Logic
+  fn vandps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vandpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vxorps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vxorpd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+
+  // Fused multiply-add
+  fn vfmadd132ps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vfmadd132pd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vfmadd213ps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vfmadd213pd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vfmadd231ps(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vfmadd231pd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+
+  // Integer
+  fn vpaddd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpaddq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpsubd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpsubq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpmulld(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpand(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpor(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpxor(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn vpcmpeqd(&mut self, dst: Operand, src1: Operand, src2: Operand);
+
+  // Lanes
+  fn vbroadcastss(&mut self, dst: Operand, src: Operand);
+  fn vbroadcastsd(&mut self, dst: Operand, src: Operand);
+  fn vpermq(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn vpermpd(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn vextractf128(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn vinsertf128(&mut self,
+                 dst: Operand,
+                 src1: Operand,
+                 src2: Operand,
+                 imm: Operand);
+
+  // Gathers
+  fn vpgatherdd(&mut self, dst: Operand, src: Operand, mask: Operand);
+  fn vgatherdps(&mut self, dst: Operand, src: Operand, mask: Operand);
+
+  // State
+  fn vzeroupper(&mut self);
+  fn vzeroall(&mut self);
+}
+
+pub trait AsmX64AvxTry {
+  // Moves
+  fn try_vmovaps(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vmovups(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vmovapd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vmovupd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vmovdqa(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vmovdqu(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Arithmetic
+  fn try_vaddps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vaddpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vsubps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vsubpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vmulps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vmulpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vdivps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vdivpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vminps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vminpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vmaxps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vmaxpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+
+  // Logic
+  fn try_vandps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vandpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vxorps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vxorpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+
+  // Fused multiply-add
+  fn try_vfmadd132ps(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult;
+  fn try_vfmadd132pd(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult;
+  fn try_vfmadd213ps(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult;
+  fn try_vfmadd213pd(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult;
+  fn try_vfmadd231ps(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult;
+  fn try_vfmadd231pd(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult;
+
+  // Integer
+  fn try_vpaddd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vpaddq(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vpsubd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vpsubq(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_vpmulld(&mut self,
+                 dst: Operand,
+                 src1: Operand,
+                 src2: Operand) -> AsmResult;
+  fn try_vpand(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_vpor(&mut self,
+              dst: Operand,
+              src1: Operand,
+              src2: Operand) -> AsmResult;
+  fn try_vpxor(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_vpcmpeqd(&mut self,
+                  dst: Operand,
+                  src1: Operand,
+                  src2: Operand) -> AsmResult;
+
+  // Lanes
+  fn try_vbroadcastss(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vbroadcastsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_vpermq(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_vpermpd(&mut self,
+                 dst: Operand,
+                 src: Operand,
+                 imm: Operand) -> AsmResult;
+  fn try_vextractf128(&mut self,
+                      dst: Operand,
+                      src: Operand,
+                      imm: Operand) -> AsmResult;
+  fn try_vinsertf128(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand,
+                     imm: Operand) -> AsmResult;
+
+  // Gathers
+  fn try_vpgatherdd(&mut self,
+                    dst: Operand,
+                    src: Operand,
+                    mask: Operand) -> AsmResult;
+  fn try_vgatherdps(&mut self,
+                    dst: Operand,
+                    src: Operand,
+                    mask: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64AvxTry for A {
+  fn try_vmovaps(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    avx_mov(self, "vmovaps", VexNone, 0x28, 0x29, dst, src)
+  }
+
+  fn try_vmovups(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    avx_mov(self, "vmovups", VexNone, 0x10, 0x11, dst, src)
+  }
+
+  fn try_vmovapd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    avx_mov(self, "vmovapd", Vex66, 0x28, 0x29, dst, src)
+  }
+
+  fn try_vmovupd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    avx_mov(self, "vmovupd", Vex66, 0x10, 0x11, dst, src)
+  }
+
+  fn try_vmovdqa(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    avx_mov(self, "vmovdqa", Vex66, 0x6f, 0x7f, dst, src)
+  }
+
+  fn try_vmovdqu(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    avx_mov(self, "vmovdqu", VexF3, 0x6f, 0x7f, dst, src)
+  }
+
+  fn try_vaddps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vaddps", false, VexNone, Vex0F, 0x58, dst, src1, src2)
+  }
+
+  fn try_vaddpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vaddpd", false, Vex66, Vex0F, 0x58, dst, src1, src2)
+  }
+
+  fn try_vsubps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vsubps", false, VexNone, Vex0F, 0x5c, dst, src1, src2)
+  }
+
+  fn try_vsubpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vsubpd", false, Vex66, Vex0F, 0x5c, dst, src1, src2)
+  }
+
+  fn try_vmulps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vmulps", false, VexNone, Vex0F, 0x59, dst, src1, src2)
+  }
+
+  fn try_vmulpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vmulpd", false, Vex66, Vex0F, 0x59, dst, src1, src2)
+  }
+
+  fn try_vdivps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vdivps", false, VexNone, Vex0F, 0x5e, dst, src1, src2)
+  }
+
+  fn try_vdivpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vdivpd", false, Vex66, Vex0F, 0x5e, dst, src1, src2)
+  }
+
+  fn try_vminps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vminps", false, VexNone, Vex0F, 0x5d, dst, src1, src2)
+  }
+
+  fn try_vminpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vminpd", false, Vex66, Vex0F, 0x5d, dst, src1, src2)
+  }
+
+  fn try_vmaxps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vmaxps", false, VexNone, Vex0F, 0x5f, dst, src1, src2)
+  }
+
+  fn try_vmaxpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vmaxpd", false, Vex66, Vex0F, 0x5f, dst, src1, src2)
+  }
+
+  fn try_vandps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vandps", false, VexNone, Vex0F, 0x54, dst, src1, src2)
+  }
+
+  fn try_vandpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vandpd", false, Vex66, Vex0F, 0x54, dst, src1, src2)
+  }
+
+  fn try_vxorps(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vxorps", false, VexNone, Vex0F, 0x57, dst, src1, src2)
+  }
+
+  fn try_vxorpd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vxorpd", false, Vex66, Vex0F, 0x57, dst, src1, src2)
+  }
+
+  fn try_vfmadd132ps(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult {
+    avx(self, "vfmadd132ps", false, Vex66, Vex0F38, 0x98, dst, src1, src2)
+  }
+
+  fn try_vfmadd132pd(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult {
+    avx(self, "vfmadd132pd", true, Vex66, Vex0F38, 0x98, dst, src1, src2)
+  }
+
+  fn try_vfmadd213ps(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult {
+    avx(self, "vfmadd213ps", false, Vex66, Vex0F38, 0xa8, dst, src1, src2)
+  }
+
+  fn try_vfmadd213pd(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult {
+    avx(self, "vfmadd213pd", true, Vex66, Vex0F38, 0xa8, dst, src1, src2)
+  }
+
+  fn try_vfmadd231ps(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult {
+    avx(self, "vfmadd231ps", false, Vex66, Vex0F38, 0xb8, dst, src1, src2)
+  }
+
+  fn try_vfmadd231pd(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand) -> AsmResult {
+    avx(self, "vfmadd231pd", true, Vex66, Vex0F38, 0xb8, dst, src1, src2)
+  }
+
+  fn try_vpaddd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vpaddd", false, Vex66, Vex0F, 0xfe, dst, src1, src2)
+  }
+
+  fn try_vpaddq(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vpaddq", false, Vex66, Vex0F, 0xd4, dst, src1, src2)
+  }
+
+  fn try_vpsubd(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vpsubd", false, Vex66, Vex0F, 0xfa, dst, src1, src2)
+  }
+
+  fn try_vpsubq(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    avx(self, "vpsubq", false, Vex66, Vex0F, 0xfb, dst, src1, src2)
+  }
+
+  fn try_vpmulld(&mut self,
+                 dst: Operand,
+                 src1: Operand,
+                 src2: Operand) -> AsmResult {
+    avx(self, "vpmulld", false, Vex66, Vex0F38, 0x40, dst, src1, src2)
+  }
+
+  fn try_vpand(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    avx(self, "vpand", false, Vex66, Vex0F, 0xdb, dst, src1, src2)
+  }
+
+  fn try_vpor(&mut self,
+              dst: Operand,
+              src1: Operand,
+              src2: Operand) -> AsmResult {
+    avx(self, "vpor", false, Vex66, Vex0F, 0xeb, dst, src1, src2)
+  }
+
+  fn try_vpxor(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    avx(self, "vpxor", false, Vex66, Vex0F, 0xef, dst, src1, src2)
+  }
+
+  fn try_vpcmpeqd(&mut self,
+                  dst: Operand,
+                  src1: Operand,
+                  src2: Operand) -> AsmResult {
+    avx(self, "vpcmpeqd", false, Vex66, Vex0F, 0x76, dst, src1, src2)
+  }
+
+  fn try_vbroadcastss(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (D(_), _) | (Y(_), _) if src.is_dm() => {
+        let l = dst.is_yreg();
+        self.vex_op(false, l, Vex66, Vex0F38, 0x18, dst, Empty, src, Empty);
+      },
+      _ => return invalid("vbroadcastss", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_vbroadcastsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (Y(_), _) if src.is_dm() => {
+        self.vex_op(false, true, Vex66, Vex0F38, 0x19, dst, Empty, src, Empty);
+      },
+      _ => return invalid("vbroadcastsd", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_vpermq(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (Y(_), _, Byte(_)) if src.is_ym() => {
+        self.vex_op(true, true, Vex66, Vex0F3A, 0x00, dst, Empty, src, imm);
+      },
+      _ => return invalid("vpermq", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_vpermpd(&mut self,
+                 dst: Operand,
+                 src: Operand,
+                 imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (Y(_), _, Byte(_)) if src.is_ym() => {
+        self.vex_op(true, true, Vex66, Vex0F3A, 0x01, dst, Empty, src, imm);
+      },
+      _ => return invalid("vpermpd", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_vextractf128(&mut self,
+                      dst: Operand,
+                      src: Operand,
+                      imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (_, Y(_), Byte(_)) if dst.is_dm() => {
+        self.vex_op(false, true, Vex66, Vex0F3A, 0x19, src, Empty, dst, imm);
+      },
+      _ => {
+        return invalid("vextractf128",
+                       &[dst, src, imm],
+                       "unsupported operands");
+      }
+    }
+    Ok(())
+  }
+
+  fn try_vinsertf128(&mut self,
+                     dst: Operand,
+                     src1: Operand,
+                     src2: Operand,
+                     imm: Operand) -> AsmResult {
+    match (dst, src1, src2, imm) {
+      (Y(_), Y(_), _, Byte(_)) if src2.is_dm() => {
+        self.vex_op(false, true, Vex66, Vex0F3A, 0x18, dst, src1, src2, imm);
+      },
+      _ => {
+        return invalid("vinsertf128",
+                       &[dst, src1, src2, imm],
+                       "unsupported operands");
+      }
+    }
+    Ok(())
+  }
+
+  fn try_vpgatherdd(&mut self,
+                    dst: Operand,
+                    src: Operand,
+                    mask: Operand) -> AsmResult {
+    gather(self, "vpgatherdd", 0x90, dst, src, mask)
+  }
+
+  fn try_vgatherdps(&mut self,
+                    dst: Operand,
+                    src: Operand,
+                    mask: Operand) -> AsmResult {
+    gather(self, "vgatherdps", 0x92, dst, src, mask)
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64AvxTry> AsmX64Avx for A {
+  fn vmovaps(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vmovaps(dst, src));
+  }
+
+  fn vmovups(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vmovups(dst, src));
+  }
+
+  fn vmovapd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vmovapd(dst, src));
+  }
+
+  fn vmovupd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vmovupd(dst, src));
+  }
+
+  fn vmovdqa(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vmovdqa(dst, src));
+  }
+
+  fn vmovdqu(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vmovdqu(dst, src));
+  }
+
+  fn vaddps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vaddps(dst, src1, src2));
+  }
+
+  fn vaddpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vaddpd(dst, src1, src2));
+  }
+
+  fn vsubps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vsubps(dst, src1, src2));
+  }
+
+  fn vsubpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vsubpd(dst, src1, src2));
+  }
+
+  fn vmulps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vmulps(dst, src1, src2));
+  }
+
+  fn vmulpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vmulpd(dst, src1, src2));
+  }
+
+  fn vdivps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vdivps(dst, src1, src2));
+  }
+
+  fn vdivpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vdivpd(dst, src1, src2));
+  }
+
+  fn vminps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vminps(dst, src1, src2));
+  }
+
+  fn vminpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vminpd(dst, src1, src2));
+  }
+
+  fn vmaxps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vmaxps(dst, src1, src2));
+  }
+
+  fn vmaxpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vmaxpd(dst, src1, src2));
+  }
+
+  fn vandps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vandps(dst, src1, src2));
+  }
+
+  fn vandpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vandpd(dst, src1, src2));
+  }
+
+  fn vxorps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vxorps(dst, src1, src2));
+  }
+
+  fn vxorpd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vxorpd(dst, src1, src2));
+  }
+
+  fn vfmadd132ps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vfmadd132ps(dst, src1, src2));
+  }
+
+  fn vfmadd132pd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vfmadd132pd(dst, src1, src2));
+  }
+
+  fn vfmadd213ps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vfmadd213ps(dst, src1, src2));
+  }
+
+  fn vfmadd213pd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vfmadd213pd(dst, src1, src2));
+  }
+
+  fn vfmadd231ps(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vfmadd231ps(dst, src1, src2));
+  }
+
+  fn vfmadd231pd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vfmadd231pd(dst, src1, src2));
+  }
+
+  fn vpaddd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpaddd(dst, src1, src2));
+  }
+
+  fn vpaddq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpaddq(dst, src1, src2));
+  }
+
+  fn vpsubd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpsubd(dst, src1, src2));
+  }
+
+  fn vpsubq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpsubq(dst, src1, src2));
+  }
+
+  fn vpmulld(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpmulld(dst, src1, src2));
+  }
+
+  fn vpand(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpand(dst, src1, src2));
+  }
+
+  fn vpor(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpor(dst, src1, src2));
+  }
+
+  fn vpxor(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpxor(dst, src1, src2));
+  }
+
+  fn vpcmpeqd(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_vpcmpeqd(dst, src1, src2));
+  }
+
+  fn vbroadcastss(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vbroadcastss(dst, src));
+  }
+
+  fn vbroadcastsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_vbroadcastsd(dst, src));
+  }
+
+  fn vpermq(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_vpermq(dst, src, imm));
+  }
+
+  fn vpermpd(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_vpermpd(dst, src, imm));
+  }
+
+  fn vextractf128(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_vextractf128(dst, src, imm));
+  }
+
+  fn vinsertf128(&mut self,
+                 dst: Operand,
+                 src1: Operand,
+                 src2: Operand,
+                 imm: Operand) {
+    or_fail(self.try_vinsertf128(dst, src1, src2, imm));
+  }
+
+  fn vpgatherdd(&mut self, dst: Operand, src: Operand, mask: Operand) {
+    or_fail(self.try_vpgatherdd(dst, src, mask));
+  }
+
+  fn vgatherdps(&mut self, dst: Operand, src: Operand, mask: Operand) {
+    or_fail(self.try_vgatherdps(dst, src, mask));
+  }
+
+  fn vzeroupper(&mut self) {
+    self.emit_vex(false, false, VexNone, Vex0F, Empty, Empty, Empty);
+    self.emitb(0x77);
+  }
+
+  fn vzeroall(&mut self) {
+    self.emit_vex(false, true, VexNone, Vex0F, Empty, Empty, Empty);
+    self.emitb(0x77);
+  }
+}
@@ -5,7 +5,12 @@ pub enum Operand {
   _Operation(u8),
   R(Register),
   D(DoubleRegister),
-  M(Register, u32),
+  Y(YmmRegister),
+  M(Register, i32),
+  MI(Register, Register, Scale, i32),
+  // VSIB memory of gathers, 128-bit forms use the low half of the index
+  VM(Register, YmmRegister, Scale, i32),
+  Rip(@mut Label),
   Byte(u8),
   Word(u16),
   Long(u32),
@@ -51,6 +56,32 @@ pub enum DoubleRegister {
   xmm15 = 15
 }
 
+pub enum YmmRegister {
+  ymm0 = 0,
+  ymm1 = 1,
+  ymm2 = 2,
+  ymm3 = 3,
+  ymm4 = 4,
+  ymm5 = 5,
+  ymm6 = 6,
+  ymm7 = 7,
+  ymm8 = 8,
+  ymm9 = 9,
+  ymm10 = 10,
+  ymm11 = 11,
+  ymm12 = 12,
+  ymm13 = 13,
+  ymm14 = 14,
+  ymm15 = 15
+}
+
+pub enum Scale {
+  Scale1 = 0,
+  Scale2 = 1,
+  Scale4 = 2,
+  Scale8 = 3
+}
+
 pub enum JumpCondition {
   IfZero,
   IfNotZero,
@@ -61,18 +92,108 @@ pub enum JumpCondition {
   IfGreater,
   IfLess,
   IfGreaterOrEqual,
-  IfLessOrEqual
+  IfLessOrEqual,
+  IfBelow,
+  IfBelowOrEqual,
+  IfAbove,
+  IfAboveOrEqual,
+  IfSign,
+  IfNotSign,
+  IfParity,
+  IfNotParity,
+  IfCarry,
+  IfNotCarry
 }
 
+pub enum OperandSize {
+  Size8,
+  Size16,
+  Size32,
+  Size64
+}
+
+// Group-1 arithmetic, the value is the /digit of the opcode
+pub enum AluOp {
+  AluAdd = 0,
+  AluOr = 1,
+  AluAdc = 2,
+  AluSbb = 3,
+  AluAnd = 4,
+  AluSub = 5,
+  AluXor = 6,
+  AluCmp = 7
+}
+
+static ALU_MNEMONICS: [[&'static str, ..4], ..8] = [
+  ["addb", "addw", "addl", "addq"],
+  ["orb", "orw", "orl", "orq"],
+  ["adcb", "adcw", "adcl", "adcq"],
+  ["sbbb", "sbbw", "sbbl", "sbbq"],
+  ["andb", "andw", "andl", "andq"],
+  ["subb", "subw", "subl", "subq"],
+  ["xorb", "xorw", "xorl", "xorq"],
+  ["cmpb", "cmpw", "cmpl", "cmpq"]
+];
+
 pub enum REXKind {
   REX,
   REXW
 }
 
+// Legacy prefix implied by VEX.pp
+pub enum VexPrefix {
+  VexNone = 0,
+  Vex66 = 1,
+  VexF3 = 2,
+  VexF2 = 3
+}
+
+// Opcode map selected by VEX.mmmmm
+pub enum VexMap {
+  Vex0F = 1,
+  Vex0F38 = 2,
+  Vex0F3A = 3
+}
+
 pub trait AsmX64Helper {
   fn emit_modrm(&mut self, r: Operand, rm: Operand);
+  fn emit_modrm_imm(&mut self, r: Operand, rm: Operand, imm: Operand);
+  fn emit_imm(&mut self, imm: Operand);
   fn emit_rex(&mut self, kind: REXKind, r: Operand, rm: Operand);
   fn emit_opt_rex(&mut self, r: Operand, rm: Operand);
+  fn emit_byte_rex(&mut self, r: Operand, rm: Operand);
+  fn emit_sized_rex(&mut self, size: OperandSize, r: Operand, rm: Operand);
+  fn emit_sse(&mut self,
+              w: bool,
+              prefix: Option<u8>,
+              escape: Option<u8>,
+              op: u8,
+              r: Operand,
+              rm: Operand,
+              imm: Operand);
+  fn emit_vex(&mut self,
+              w: bool,
+              l: bool,
+              pp: VexPrefix,
+              map: VexMap,
+              r: Operand,
+              v: Operand,
+              rm: Operand);
+  fn vex_op(&mut self,
+            w: bool,
+            l: bool,
+            pp: VexPrefix,
+            map: VexMap,
+            op: u8,
+            r: Operand,
+            v: Operand,
+            rm: Operand,
+            imm: Operand);
+  fn emit_alu(&mut self,
+              op: AluOp,
+              size: OperandSize,
+              dst: Operand,
+              src: Operand) -> AsmResult;
 }
 
 pub trait AsmX64 {
@@ -80,6 +201,72 @@ pub trait AsmX64 {
   fn int3(&mut self);
 }
 
+// [rip + label], the label is shared so it lives until it is bound
+pub fn rip(l: @mut Label) -> Operand {
+  Rip(l)
+}
+
+pub fn invalid(mnemonic: &'static str,
+               operands: &[Operand],
+               reason: &'static str) -> AsmResult {
+  Err(AsmError {
+    mnemonic: mnemonic,
+    operands: operands.map(|op| op.kind()),
+    reason: reason
+  })
+}
+
+impl JumpCondition {
+  // Low nibble of jcc opcodes
+  fn code(&self) -> u8 {
+    match *self {
+      IfOverlow => 0x0,
+      IfNoOverlow => 0x1,
+      IfBelow | IfCarry => 0x2,
+      IfAboveOrEqual | IfNotCarry => 0x3,
+      IfZero | IfEqual => 0x4,
+      IfNotZero | IfNotEqual => 0x5,
+      IfBelowOrEqual => 0x6,
+      IfAbove => 0x7,
+      IfSign => 0x8,
+      IfNotSign => 0x9,
+      IfParity => 0xa,
+      IfNotParity => 0xb,
+      IfLess => 0xc,
+      IfGreaterOrEqual => 0xd,
+      IfLessOrEqual => 0xe,
+      IfGreater => 0xf
+    }
+  }
+}
+
+impl AluOp {
+  fn mnemonic(&self, size: OperandSize) -> &'static str {
+    ALU_MNEMONICS[*self as uint][size as uint]
+  }
+}
+
+impl OperandSize {
+  // Full-size opcodes are byte ones with the lowest bit set
+  fn opcode(&self, op: u8) -> u8 {
+    match *self {
+      Size8 => op,
+      _ => op | 1
+    }
+  }
+
+  // 64-bit operations take sign-extended 32-bit immediates
+  fn is_imm(&self, imm: &Operand) -> bool {
+    match (*self, *imm) {
+      (Size8, Byte(_)) => true,
+      (Size16, Word(_)) => true,
+      (Size32, Long(_)) => true,
+      (Size64, Long(_)) => true,
+      _ => false
+    }
+  }
+}
+
 impl Register {
   fn high(&self) -> u8 { ((*self as u8) >> 3) & 1 }
   fn low(&self) -> u8 { (*self as u8) & 0x7 }
@@ -90,52 +277,159 @@ impl DoubleRegister {
   fn low(&self) -> u8 { (*self as u8) & 0x7 }
 }
 
+impl YmmRegister {
+  fn high(&self) -> u8 { ((*self as u8) >> 3) & 1 }
+  fn low(&self) -> u8 { (*self as u8) & 0x7 }
+}
+
 impl Operand {
   fn is_reg(&self) -> bool { match self { &R(_) => true, _ => false } }
   fn is_dreg(&self) -> bool { match self { &D(_) => true, _ => false } }
-  fn is_mem(&self) -> bool { match self { &M(_, _) => true, _ => false } }
+  fn is_yreg(&self) -> bool { match self { &Y(_) => true, _ => false } }
+  fn is_mem(&self) -> bool {
+    match self {
+      _ if self.has_stack_index() => false,
+      &M(_, _) | &MI(_, _, _, _) | &Rip(_) => true,
+      _ => false
+    }
+  }
+
+  // rsp in the index field means "no index"
+  fn has_stack_index(&self) -> bool {
+    match self { &MI(_, rsp, _, _) => true, _ => false }
+  }
   fn is_rm(&self) -> bool { self.is_reg() || self.is_mem() }
   fn is_dm(&self) -> bool { self.is_dreg() || self.is_mem() }
+  fn is_ym(&self) -> bool { self.is_yreg() || self.is_mem() }
 
   fn is_operation(&self) -> bool {
     match self { &_Operation(_) => true, _ => false }
   }
 
+  // spl, bpl, sil and dil are encoded as ah, ch, dh and bh without REX
+  fn is_rex_byte(&self) -> bool {
+    match self { &R(ref r) => *r as u8 >= 4, _ => false }
+  }
+
+  fn kind(&self) -> &'static str {
+    match self {
+      &Empty => "Empty",
+      &_Operation(_) => "_Operation",
+      &R(_) => "R",
+      &D(_) => "D",
+      &Y(_) => "Y",
+      &M(_, _) => "M",
+      &MI(_, _, _, _) => "MI",
+      &VM(_, _, _, _) => "VM",
+      &Rip(_) => "Rip",
+      &Byte(_) => "Byte",
+      &Word(_) => "Word",
+      &Long(_) => "Long",
+      &Quad(_) => "Quad"
+    }
+  }
+
+  fn imm_size(&self) -> int {
+    match self {
+      &Byte(_) => 1,
+      &Word(_) => 2,
+      &Long(_) => 4,
+      &Quad(_) => 8,
+      _ => 0
+    }
+  }
+
   fn high(&self) -> u8 {
     match self {
-      &R(ref r) | &M(ref r, _) => r.high(),
+      &R(ref r) | &M(ref r, _) | &MI(ref r, _, _, _) => r.high(),
+      &VM(ref r, _, _, _) => r.high(),
       &D(ref d) => d.high(),
+      &Y(ref y) => y.high(),
+      _ => 0
+    }
+  }
+
+  fn index_high(&self) -> u8 {
+    match self {
+      &MI(_, ref i, _, _) => i.high(),
+      &VM(_, ref i, _, _) => i.high(),
       _ => 0
     }
   }
 
   fn low(&self) -> u8 {
     match self {
-      &R(ref r) | &M(ref r, _) => r.low(),
+      &R(ref r) | &M(ref r, _) | &MI(ref r, _, _, _) => r.low(),
+      &VM(ref r, _, _, _) => r.low(),
       &D(ref d) => d.low(),
+      &Y(ref y) => y.low(),
       &_Operation(op) => op & 7,
       _ => 0
     }
   }
+
+  fn num(&self) -> u8 { (self.high() << 3) | self.low() }
 }
 
 impl<M: AsmBuffer> AsmX64Helper for M {
   fn emit_modrm(&mut self, r: Operand, rm: Operand) {
-    assert!(r.is_reg() || r.is_dreg() || r.is_operation());
+    self.emit_modrm_imm(r, rm, Empty);
+  }
+
+  fn emit_modrm_imm(&mut self, r: Operand, rm: Operand, imm: Operand) {
+    assert!(r.is_reg() || r.is_dreg() || r.is_yreg() || r.is_operation());
     let rbit = r.low() << 3;
 
     match rm {
-      M(ref rm, 0) => self.emitb(rbit | rm.low()),
-      M(ref rm, b) if b <= 0xff => {
-        self.emitb(0b0100_0000 | rbit | rm.low());
-        self.emitb(b as u8)
+      M(ref base, disp) | MI(ref base, _, _, disp) |
+      VM(ref base, _, _, disp) => {
+        // rbp and r13 with mod=00 mean disp32 without a base
+        let mode = if disp == 0 && base.low() != 0b101 {
+          0b00
+        } else if -128 <= disp && disp <= 127 {
+          0b01
+        } else {
+          0b10
+        };
+
+        let sib = match rm {
+          MI(_, ref index, scale, _) => {
+            // rsp in the index field means "no index"
+            assert!(*index as u8 != rsp as u8);
+            Some(((scale as u8) << 6) | (index.low() << 3) | base.low())
+          },
+          // Any vector register can be an index, ymm4 included
+          VM(_, ref index, scale, _) => {
+            Some(((scale as u8) << 6) | (index.low() << 3) | base.low())
+          },
+          // rsp and r12 can only be used as a base through SIB
+          _ if base.low() == 0b100 => Some(0x24),
+          _ => None
+        };
+
+        match sib {
+          Some(sib) => {
+            self.emitb((mode << 6) | rbit | 0b100);
+            self.emitb(sib);
+          },
+          None => self.emitb((mode << 6) | rbit | base.low())
+        }
+
+        match mode {
+          0b01 => self.emitb(disp as u8),
+          0b10 => self.emitl(disp as u32),
+          _ => ()
+        }
       },
-      M(ref rm, l) => {
-        self.emitb(0b1000_0000 | rbit | rm.low());
-        self.emitl(l)
+      Rip(l) => {
+        // Displacement is relative to the end of instruction
+        self.emitb(rbit | 0b101);
+        let nudge = -4 - imm.imm_size();
+        self.emit_use(&mut *l, RelocRelative, RelocLong, nudge);
       },
       R(ref rm) => self.emitb(0b1100_0000 | rbit | rm.low()),
       D(ref rm) => self.emitb(0b1100_0000 | rbit | rm.low()),
+      Y(ref rm) => self.emitb(0b1100_0000 | rbit | rm.low()),
       Byte(_) => self.emitb(rbit),
       Word(_) => self.emitb(rbit),
       Long(_) => self.emitb(rbit),
@@ -143,20 +437,168 @@ impl<M: AsmBuffer> AsmX64Helper for M {
       Empty => self.emitb(rbit),
       _ => fail!()
     }
+
+    self.emit_imm(imm);
+  }
+
+  fn emit_imm(&mut self, imm: Operand) {
+    match imm {
+      Byte(b) => self.emitb(b),
+      Word(w) => self.emitw(w),
+      Long(l) => self.emitl(l),
+      Quad(q) => self.emitq(q),
+      Empty => (),
+      _ => fail!()
+    }
   }
 
   fn emit_rex(&mut self, kind: REXKind, r: Operand, rm: Operand) {
     self.emitb(match kind {
       REX => 0b0100_0000,
       REXW => 0b0100_1000
-    } | (r.high() << 2) | rm.high());
+    } | (r.high() << 2) | (rm.index_high() << 1) | rm.high());
   }
 
   fn emit_opt_rex(&mut self, r: Operand, rm: Operand) {
-    if (r.high() != 0 || rm.high() != 0) {
+    if (r.high() != 0 || rm.high() != 0 || rm.index_high() != 0) {
       self.emit_rex(REX, r, rm);
     }
   }
+
+  fn emit_byte_rex(&mut self, r: Operand, rm: Operand) {
+    if r.is_rex_byte() || rm.is_rex_byte() {
+      self.emit_rex(REX, r, rm);
+    } else {
+      self.emit_opt_rex(r, rm);
+    }
+  }
+
+  fn emit_sized_rex(&mut self, size: OperandSize, r: Operand, rm: Operand) {
+    match size {
+      Size8 => self.emit_byte_rex(r, rm),
+      Size16 => {
+        self.emitb(0x66);
+        self.emit_opt_rex(r, rm);
+      },
+      Size32 => self.emit_opt_rex(r, rm),
+      Size64 => self.emit_rex(REXW, r, rm)
+    }
+  }
+
+  fn emit_sse(&mut self,
+              w: bool,
+              prefix: Option<u8>,
+              escape: Option<u8>,
+              op: u8,
+              r: Operand,
+              rm: Operand,
+              imm: Operand) {
+    // Mandatory prefix goes before REX
+    match prefix {
+      Some(p) => self.emitb(p),
+      None => ()
+    }
+    if w {
+      self.emit_rex(REXW, r, rm);
+    } else {
+      self.emit_opt_rex(r, rm);
+    }
+
+    // 0F 38 and 0F 3A opcodes carry the middle byte in `escape`
+    self.emitb(0x0f);
+    match escape {
+      Some(e) => self.emitb(e),
+      None => ()
+    }
+    self.emitb(op);
+    self.emit_modrm_imm(r, rm, imm);
+  }
+
+  fn emit_vex(&mut self,
+              w: bool,
+              l: bool,
+              pp: VexPrefix,
+              map: VexMap,
+              r: Operand,
+              v: Operand,
+              rm: Operand) {
+    // R, X, B and vvvv are stored inverted
+    let vlpp = ((!v.num() & 0xf) << 3) |
+               (if l { 0x04 } else { 0 }) |
+               (pp as u8);
+
+    match map {
+      // Two-byte form has no room for W, X, B and the map
+      Vex0F if !w && rm.index_high() == 0 && rm.high() == 0 => {
+        self.emitb(0xc5);
+        self.emitb(((r.high() ^ 1) << 7) | vlpp);
+      },
+      _ => {
+        self.emitb(0xc4);
+        self.emitb(((r.high() ^ 1) << 7) |
+                   ((rm.index_high() ^ 1) << 6) |
+                   ((rm.high() ^ 1) << 5) |
+                   (map as u8));
+        self.emitb((if w { 0x80 } else { 0 }) | vlpp);
+      }
+    }
+  }
+
+  fn vex_op(&mut self,
+            w: bool,
+            l: bool,
+            pp: VexPrefix,
+            map: VexMap,
+            op: u8,
+            r: Operand,
+            v: Operand,
+            rm: Operand,
+            imm: Operand) {
+    self.emit_vex(w, l, pp, map, r, v, rm);
+    self.emitb(op);
+    self.emit_modrm_imm(r, rm, imm);
+  }
+
+  // add, or, adc, sbb, and, sub, xor and cmp differ only in /digit
+  fn emit_alu(&mut self,
+              op: AluOp,
+              size: OperandSize,
+              dst: Operand,
+              src: Operand) -> AsmResult {
+    let name = op.mnemonic(size);
+    let digit = op as u8;
+    let op = digit << 3;
+
+    match (dst, src) {
+      (R(rax), _) if size.is_imm(&src) => {
+        self.emit_sized_rex(size, Empty, Empty);
+        self.emitb(size.opcode(op | 0x04));
+        self.emit_imm(src);
+      },
+      (_, Byte(b)) if dst.is_rm() && !size.is_imm(&src) => {
+        self.emit_sized_rex(size, Empty, dst);
+        self.emitb(0x83);
+        self.emit_modrm_imm(_Operation(digit), dst, Byte(b));
+      },
+      (_, _) if dst.is_rm() && size.is_imm(&src) => {
+        self.emit_sized_rex(size, Empty, dst);
+        self.emitb(size.opcode(0x80));
+        self.emit_modrm_imm(_Operation(digit), dst, src);
+      },
+      (R(_), _) if src.is_rm() => {
+        self.emit_sized_rex(size, dst, src);
+        self.emitb(size.opcode(op | 0x02));
+        self.emit_modrm(dst, src);
+      },
+      (_, R(_)) if dst.is_rm() => {
+        self.emit_sized_rex(size, src, dst);
+        self.emitb(size.opcode(op));
+        self.emit_modrm(src, dst);
+      },
+      _ => return invalid(name, &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
 }
 
 impl<M: AsmBuffer+AsmX64Helper> AsmX64 for M {
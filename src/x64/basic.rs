@@ -1,19 +1,75 @@
 use asm::*;
 use asm::x64::base::*;
 
+fn mov<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                  name: &'static str,
+                                  size: OperandSize,
+                                  dst: Operand,
+                                  src: Operand) -> AsmResult {
+  match (dst, src) {
+    (R(_), _) if src.is_rm() => {
+      m.emit_sized_rex(size, dst, src);
+      m.emitb(size.opcode(0x8a));
+      m.emit_modrm(dst, src);
+    },
+    (_, R(_)) if dst.is_rm() => {
+      m.emit_sized_rex(size, src, dst);
+      m.emitb(size.opcode(0x88));
+      m.emit_modrm(src, dst);
+    },
+    (R(_), _) if size.is_imm(&src) => {
+      m.emit_sized_rex(size, Empty, dst);
+      m.emitb(match size { Size8 => 0xb0, _ => 0xb8 } | dst.low());
+      m.emit_imm(src);
+    },
+    (_, _) if dst.is_rm() && size.is_imm(&src) => {
+      m.emit_sized_rex(size, Empty, dst);
+      m.emitb(size.opcode(0xc6));
+      m.emit_modrm_imm(_Operation(0), dst, src);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
 pub trait AsmX64Basic {
   fn movq(&mut self, dst: Operand, src: Operand);
   fn movqzxb(&mut self, dst: Operand, src: Operand);
   fn movqzxl(&mut self, dst: Operand, src: Operand);
   fn movq_proc(&mut self, dst: Operand, l: &mut Label);
+  fn leaq(&mut self, dst: Operand, src: Operand);
   fn xchgq(&mut self, dst: Operand, src: Operand);
   fn pushq(&mut self, op: Operand);
   fn popq(&mut self, op: Operand);
   fn ret(&mut self, r: Operand);
+  fn movl(&mut self, dst: Operand, src: Operand);
+  fn movw(&mut self, dst: Operand, src: Operand);
+  fn movb(&mut self, dst: Operand, src: Operand);
+  fn movqsxb(&mut self, dst: Operand, src: Operand);
+  fn movqsxw(&mut self, dst: Operand, src: Operand);
+  fn movqsxd(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmX64Helper> AsmX64Basic for A {
-  fn movq(&mut self, dst: Operand, src: Operand) {
+pub trait AsmX64BasicTry {
+  fn try_movq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movqzxb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movqzxl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movq_proc(&mut self, dst: Operand, l: &mut Label) -> AsmResult;
+  fn try_leaq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xchgq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pushq(&mut self, op: Operand) -> AsmResult;
+  fn try_popq(&mut self, op: Operand) -> AsmResult;
+  fn try_ret(&mut self, r: Operand) -> AsmResult;
+  fn try_movl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movqsxb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movqsxw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movqsxd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64BasicTry for A {
+  fn try_movq(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(_), _) if src.is_rm() => {
         self.emit_rex(REXW, dst, src);
@@ -28,19 +84,19 @@ impl<A: AsmBuffer+AsmX64Helper> AsmX64Basic for A {
       (_, Long(l)) if dst.is_rm() => {
         self.emit_rex(REXW, Empty, dst);
         self.emitb(0xc7);
-        self.emit_modrm(_Operation(0), dst);
-        self.emitl(l);
+        self.emit_modrm_imm(_Operation(0), dst, Long(l));
       },
       (R(_), Quad(q)) => {
         self.emit_rex(REXW, dst, src);
         self.emitb(0xb8 | dst.low());
         self.emitq(q);
       },
-      _ => fail!()
+      _ => return invalid("movq", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movqzxb(&mut self, dst: Operand, src: Operand) {
+  fn try_movqzxb(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(_), _) if src.is_rm() => {
         self.emit_rex(REXW, dst, src);
@@ -48,11 +104,12 @@ impl<A: AsmBuffer+AsmX64Helper> AsmX64Basic for A {
         self.emitb(0xb6);
         self.emit_modrm(dst, src);
       },
-      _ => fail!()
+      _ => return invalid("movqzxb", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movqzxl(&mut self, dst: Operand, src: Operand) {
+  fn try_movqzxl(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(_), _) if src.is_rm() => {
         self.emit_rex(REXW, dst, src);
@@ -60,22 +117,36 @@ impl<A: AsmBuffer+AsmX64Helper> AsmX64Basic for A {
         self.emitb(0xb7);
         self.emit_modrm(dst, src);
       },
-      _ => fail!()
+      _ => return invalid("movqzxl", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn movq_proc(&mut self, dst: Operand, l: &mut Label) {
+  fn try_movq_proc(&mut self, dst: Operand, l: &mut Label) -> AsmResult {
     match dst {
       R(_) => {
         self.emit_rex(REXW, dst, Empty);
         self.emitb(0xb8 | dst.low());
         self.emit_use(l, RelocAbsolute, RelocQuad, 0);
       },
-      _ => fail!()
+      _ => return invalid("movq_proc", &[dst], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn xchgq(&mut self, dst: Operand, src: Operand) {
+  fn try_leaq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_mem() => {
+        self.emit_rex(REXW, dst, src);
+        self.emitb(0x8d);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("leaq", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_xchgq(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
       (R(rax), R(_)) => {
         self.emit_rex(REXW, dst, src);
@@ -95,18 +166,19 @@ impl<A: AsmBuffer+AsmX64Helper> AsmX64Basic for A {
         self.emitb(0x87);
         self.emit_modrm(src, dst);
       },
-      _ => fail!()
+      _ => return invalid("xchgq", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn pushq(&mut self, op: Operand) {
+  fn try_pushq(&mut self, op: Operand) -> AsmResult {
     match op {
       R(_) => {
         self.emit_opt_rex(Empty, op);
         self.emitb(0xff);
         self.emit_modrm(_Operation(6), op);
       },
-      M(_, _) => {
+      _ if op.is_mem() => {
         self.emit_opt_rex(Empty, op);
         self.emitb(0xff);
         self.emit_modrm(_Operation(6), op);
@@ -119,33 +191,148 @@ impl<A: AsmBuffer+AsmX64Helper> AsmX64Basic for A {
         self.emitb(0x68);
         self.emitl(l);
       },
-      _ => fail!()
+      _ => return invalid("pushq", &[op], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn popq(&mut self, op: Operand) {
+  fn try_popq(&mut self, op: Operand) -> AsmResult {
     match op {
       R(_) => {
         self.emit_opt_rex(Empty, op);
         self.emitb(0x58 | op.low());
       },
-      M(_, _) => {
+      _ if op.is_mem() => {
         self.emit_opt_rex(Empty, op);
         self.emitb(0x8f);
         self.emit_modrm(_Operation(0), op);
       },
-      _ => fail!()
+      _ => return invalid("popq", &[op], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn ret(&mut self, r: Operand) {
+  fn try_ret(&mut self, r: Operand) -> AsmResult {
     match r {
       Empty => self.emitb(0xc3),
       Word(w) => {
         self.emitb(0xc2);
         self.emitw(w as u16);
       },
-      _ => fail!()
+      _ => return invalid("ret", &[r], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_movl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    mov(self, "movl", Size32, dst, src)
+  }
+
+  fn try_movw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    mov(self, "movw", Size16, dst, src)
+  }
+
+  fn try_movb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    mov(self, "movb", Size8, dst, src)
+  }
+
+  fn try_movqsxb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_rm() => {
+        self.emit_rex(REXW, dst, src);
+        self.emitb(0x0f);
+        self.emitb(0xbe);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("movqsxb", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_movqsxw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_rm() => {
+        self.emit_rex(REXW, dst, src);
+        self.emitb(0x0f);
+        self.emitb(0xbf);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("movqsxw", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_movqsxd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_rm() => {
+        self.emit_rex(REXW, dst, src);
+        self.emitb(0x63);
+        self.emit_modrm(dst, src);
+      },
+      _ => return invalid("movqsxd", &[dst, src], "unsupported operands")
     }
+    Ok(())
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64BasicTry> AsmX64Basic for A {
+  fn movq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movq(dst, src));
+  }
+
+  fn movqzxb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movqzxb(dst, src));
+  }
+
+  fn movqzxl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movqzxl(dst, src));
+  }
+
+  fn movq_proc(&mut self, dst: Operand, l: &mut Label) {
+    or_fail(self.try_movq_proc(dst, l));
+  }
+
+  fn leaq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_leaq(dst, src));
+  }
+
+  fn xchgq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xchgq(dst, src));
+  }
+
+  fn pushq(&mut self, op: Operand) {
+    or_fail(self.try_pushq(op));
+  }
+
+  fn popq(&mut self, op: Operand) {
+    or_fail(self.try_popq(op));
+  }
+
+  fn ret(&mut self, r: Operand) {
+    or_fail(self.try_ret(r));
+  }
+
+  fn movl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movl(dst, src));
+  }
+
+  fn movw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movw(dst, src));
+  }
+
+  fn movb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movb(dst, src));
+  }
+
+  fn movqsxb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movqsxb(dst, src));
+  }
+
+  fn movqsxw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movqsxw(dst, src));
+  }
+
+  fn movqsxd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movqsxd(dst, src));
   }
 }
@@ -0,0 +1,213 @@
+use asm::*;
+use asm::x64::base::*;
+
+// dst = op(src1, src2), src1 goes into VEX.vvvv
+fn rvm<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                  name: &'static str,
+                                  pp: VexPrefix,
+                                  op: u8,
+                                  dst: Operand,
+                                  src1: Operand,
+                                  src2: Operand) -> AsmResult {
+  match (dst, src1) {
+    (R(_), R(_)) if src2.is_rm() => {
+      m.vex_op(true, false, pp, Vex0F38, op, dst, src1, src2, Empty);
+    },
+    _ => return invalid(name, &[dst, src1, src2], "unsupported operands")
+  }
+  Ok(())
+}
+
+// dst = op(src1, src2), src2 goes into VEX.vvvv
+fn rmv<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                  name: &'static str,
+                                  pp: VexPrefix,
+                                  op: u8,
+                                  dst: Operand,
+                                  src1: Operand,
+                                  src2: Operand) -> AsmResult {
+  match (dst, src2) {
+    (R(_), R(_)) if src1.is_rm() => {
+      m.vex_op(true, false, pp, Vex0F38, op, dst, src2, src1, Empty);
+    },
+    _ => return invalid(name, &[dst, src1, src2], "unsupported operands")
+  }
+  Ok(())
+}
+
+pub trait AsmX64Bmi {
+  fn andnq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn bextrq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn blsrq(&mut self, dst: Operand, src: Operand);
+  fn shlxq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn sarxq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn shrxq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn pdepq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn pextq(&mut self, dst: Operand, src1: Operand, src2: Operand);
+  fn mulxq(&mut self, hi: Operand, lo: Operand, src: Operand);
+  fn rorxq(&mut self, dst: Operand, src: Operand, imm: Operand);
+}
+
+pub trait AsmX64BmiTry {
+  fn try_andnq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_bextrq(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult;
+  fn try_blsrq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shlxq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_sarxq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_shrxq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_pdepq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_pextq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult;
+  fn try_mulxq(&mut self,
+               hi: Operand,
+               lo: Operand,
+               src: Operand) -> AsmResult;
+  fn try_rorxq(&mut self,
+               dst: Operand,
+               src: Operand,
+               imm: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64BmiTry for A {
+  fn try_andnq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    rvm(self, "andnq", VexNone, 0xf2, dst, src1, src2)
+  }
+
+  fn try_bextrq(&mut self,
+                dst: Operand,
+                src1: Operand,
+                src2: Operand) -> AsmResult {
+    rmv(self, "bextrq", VexNone, 0xf7, dst, src1, src2)
+  }
+
+  fn try_blsrq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_rm() => {
+        self.vex_op(true, false, VexNone, Vex0F38, 0xf3, _Operation(1),
+                    dst, src, Empty);
+      },
+      _ => return invalid("blsrq", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_shlxq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    rmv(self, "shlxq", Vex66, 0xf7, dst, src1, src2)
+  }
+
+  fn try_sarxq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    rmv(self, "sarxq", VexF3, 0xf7, dst, src1, src2)
+  }
+
+  fn try_shrxq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    rmv(self, "shrxq", VexF2, 0xf7, dst, src1, src2)
+  }
+
+  fn try_pdepq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    rvm(self, "pdepq", VexF2, 0xf5, dst, src1, src2)
+  }
+
+  fn try_pextq(&mut self,
+               dst: Operand,
+               src1: Operand,
+               src2: Operand) -> AsmResult {
+    rvm(self, "pextq", VexF3, 0xf5, dst, src1, src2)
+  }
+
+  fn try_mulxq(&mut self,
+               hi: Operand,
+               lo: Operand,
+               src: Operand) -> AsmResult {
+    rvm(self, "mulxq", VexF2, 0xf6, hi, lo, src)
+  }
+
+  fn try_rorxq(&mut self,
+               dst: Operand,
+               src: Operand,
+               imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (R(_), _, Byte(_)) if src.is_rm() => {
+        self.vex_op(true, false, VexF2, Vex0F3A, 0xf0, dst, Empty, src, imm);
+      },
+      _ => return invalid("rorxq", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64BmiTry> AsmX64Bmi for A {
+  fn andnq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_andnq(dst, src1, src2));
+  }
+
+  fn bextrq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_bextrq(dst, src1, src2));
+  }
+
+  fn blsrq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_blsrq(dst, src));
+  }
+
+  fn shlxq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_shlxq(dst, src1, src2));
+  }
+
+  fn sarxq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_sarxq(dst, src1, src2));
+  }
+
+  fn shrxq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_shrxq(dst, src1, src2));
+  }
+
+  fn pdepq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_pdepq(dst, src1, src2));
+  }
+
+  fn pextq(&mut self, dst: Operand, src1: Operand, src2: Operand) {
+    or_fail(self.try_pextq(dst, src1, src2));
+  }
+
+  fn mulxq(&mut self, hi: Operand, lo: Operand, src: Operand) {
+    or_fail(self.try_mulxq(hi, lo, src));
+  }
+
+  fn rorxq(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_rorxq(dst, src, imm));
+  }
+}
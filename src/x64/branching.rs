@@ -1,102 +1,248 @@
 use asm::*;
 use asm::x64::base::*;
 
+fn test<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                   name: &'static str,
+                                   size: OperandSize,
+                                   dst: Operand,
+                                   src: Operand) -> AsmResult {
+  match (dst, src) {
+    (R(rax), _) if size.is_imm(&src) => {
+      m.emit_sized_rex(size, Empty, Empty);
+      m.emitb(size.opcode(0xa8));
+      m.emit_imm(src);
+    },
+    (_, _) if dst.is_rm() && size.is_imm(&src) => {
+      m.emit_sized_rex(size, Empty, dst);
+      m.emitb(size.opcode(0xf6));
+      m.emit_modrm_imm(_Operation(0), dst, src);
+    },
+    (_, R(_)) if dst.is_rm() => {
+      m.emit_sized_rex(size, src, dst);
+      m.emitb(size.opcode(0x84));
+      m.emit_modrm(src, dst);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
 pub trait AsmX64Branching {
   fn testq(&mut self, dst: Operand, src: Operand);
   fn cmpq(&mut self, dst: Operand, src: Operand);
   fn jmpq(&mut self, target: Operand);
   fn jmpl(&mut self, l: &mut Label);
+  fn jmpb(&mut self, l: &mut Label);
   fn jccl(&mut self, c: JumpCondition, l: &mut Label);
+  fn jccb(&mut self, c: JumpCondition, l: &mut Label);
   fn callq(&mut self, target: Operand);
+  fn cmovq(&mut self, c: JumpCondition, dst: Operand, src: Operand);
+  fn cmovl(&mut self, c: JumpCondition, dst: Operand, src: Operand);
+  fn setcc(&mut self, c: JumpCondition, dst: Operand);
+  fn testl(&mut self, dst: Operand, src: Operand);
+  fn testw(&mut self, dst: Operand, src: Operand);
+  fn testb(&mut self, dst: Operand, src: Operand);
+  fn cmpl(&mut self, dst: Operand, src: Operand);
+  fn cmpw(&mut self, dst: Operand, src: Operand);
+  fn cmpb(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmX64Helper> AsmX64Branching for A {
-  fn testq(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0xa9);
-        self.emitl(l);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0xf7);
-        self.emit_modrm(_Operation(0), dst);
-        self.emitl(l);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emit_rex(REXW, src, dst);
-        self.emitb(0x85);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
+pub trait AsmX64BranchingTry {
+  fn try_testq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_jmpq(&mut self, target: Operand) -> AsmResult;
+  fn try_callq(&mut self, target: Operand) -> AsmResult;
+  fn try_cmovq(&mut self,
+               c: JumpCondition,
+               dst: Operand,
+               src: Operand) -> AsmResult;
+  fn try_cmovl(&mut self,
+               c: JumpCondition,
+               dst: Operand,
+               src: Operand) -> AsmResult;
+  fn try_setcc(&mut self, c: JumpCondition, dst: Operand) -> AsmResult;
+  fn try_testl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_testw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_testb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_cmpb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64BranchingTry for A {
+  fn try_testq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    test(self, "testq", Size64, dst, src)
+  }
+
+  fn try_cmpq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size64, dst, src)
+  }
+
+  fn try_jmpq(&mut self, target: Operand) -> AsmResult {
+    if !target.is_rm() {
+      return invalid("jmpq", &[target], "expected register or memory");
     }
+    self.emit_opt_rex(Empty, target);
+    self.emitb(0xff);
+    self.emit_modrm(Empty, target);
+    Ok(())
   }
 
-  fn cmpq(&mut self, dst: Operand, src: Operand) {
+  fn try_callq(&mut self, target: Operand) -> AsmResult {
+    if !target.is_rm() {
+      return invalid("callq", &[target], "expected register or memory");
+    }
+    self.emit_rex(REXW, Empty, target);
+    self.emitb(0xff);
+    self.emit_modrm(_Operation(2), target);
+    Ok(())
+  }
+
+  fn try_cmovq(&mut self,
+               c: JumpCondition,
+               dst: Operand,
+               src: Operand) -> AsmResult {
     match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0x3d);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(7), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(7), dst);
-        self.emitl(l);
-      },
       (R(_), _) if src.is_rm() => {
         self.emit_rex(REXW, dst, src);
-        self.emitb(0x3b);
+        self.emitb(0x0f);
+        self.emitb(0x40 | c.code());
         self.emit_modrm(dst, src);
       },
-      (_, R(_)) if dst.is_rm() => {
-        self.emit_rex(REXW, src, dst);
-        self.emitb(0x39);
-        self.emit_modrm(src, dst);
+      _ => return invalid("cmovq", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_cmovl(&mut self,
+               c: JumpCondition,
+               dst: Operand,
+               src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), _) if src.is_rm() => {
+        self.emit_opt_rex(dst, src);
+        self.emitb(0x0f);
+        self.emitb(0x40 | c.code());
+        self.emit_modrm(dst, src);
       },
-      _ => fail!()
+      _ => return invalid("cmovl", &[dst, src], "unsupported operands")
     }
+    Ok(())
+  }
+
+  fn try_setcc(&mut self, c: JumpCondition, dst: Operand) -> AsmResult {
+    if !dst.is_rm() {
+      return invalid("setcc", &[dst], "expected register or memory");
+    }
+    self.emit_byte_rex(Empty, dst);
+    self.emitb(0x0f);
+    self.emitb(0x90 | c.code());
+    self.emit_modrm(_Operation(0), dst);
+    Ok(())
+  }
+
+  fn try_testl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    test(self, "testl", Size32, dst, src)
+  }
+
+  fn try_testw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    test(self, "testw", Size16, dst, src)
+  }
+
+  fn try_testb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    test(self, "testb", Size8, dst, src)
+  }
+
+  fn try_cmpl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size32, dst, src)
+  }
+
+  fn try_cmpw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size16, dst, src)
+  }
+
+  fn try_cmpb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluCmp, Size8, dst, src)
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64BranchingTry> AsmX64Branching for A {
+  fn testq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_testq(dst, src));
+  }
+
+  fn cmpq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpq(dst, src));
   }
 
   fn jmpq(&mut self, target: Operand) {
-    self.emit_opt_rex(Empty, target);
-    self.emitb(0xff);
-    self.emit_modrm(Empty, target);
+    or_fail(self.try_jmpq(target));
   }
 
   fn jmpl(&mut self, l: &mut Label) {
+    if self.is_short_jump(l, 2) {
+      return self.jmpb(l);
+    }
     self.emitb(0xe9);
     self.emit_use(l, RelocRelative, RelocLong, -4);
   }
 
+  fn jmpb(&mut self, l: &mut Label) {
+    self.emitb(0xeb);
+    self.emit_jump_use(l);
+  }
+
   fn jccl(&mut self, c: JumpCondition, l: &mut Label) {
+    if self.is_short_jump(l, 2) {
+      return self.jccb(c, l);
+    }
     self.emitb(0x0f);
-    self.emitb(match c {
-      IfZero => 0x84,
-      IfNotZero => 0x85,
-      IfOverlow => 0x80,
-      IfNoOverlow => 0x81,
-      IfEqual => 0x84,
-      IfNotEqual => 0x85,
-      IfGreater => 0x8f,
-      IfLess => 0x8c,
-      IfGreaterOrEqual => 0x8d,
-      IfLessOrEqual => 0x8e
-    });
+    self.emitb(0x80 | c.code());
     self.emit_use(l, RelocRelative, RelocLong, -4);
   }
 
+  fn jccb(&mut self, c: JumpCondition, l: &mut Label) {
+    self.emitb(0x70 | c.code());
+    self.emit_jump_use(l);
+  }
+
   fn callq(&mut self, target: Operand) {
-    self.emit_rex(REXW, Empty, target);
-    self.emitb(0xff);
-    self.emit_modrm(_Operation(2), target);
+    or_fail(self.try_callq(target));
+  }
+
+  fn cmovq(&mut self, c: JumpCondition, dst: Operand, src: Operand) {
+    or_fail(self.try_cmovq(c, dst, src));
+  }
+
+  fn cmovl(&mut self, c: JumpCondition, dst: Operand, src: Operand) {
+    or_fail(self.try_cmovl(c, dst, src));
+  }
+
+  fn setcc(&mut self, c: JumpCondition, dst: Operand) {
+    or_fail(self.try_setcc(c, dst));
+  }
+
+  fn testl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_testl(dst, src));
+  }
+
+  fn testw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_testw(dst, src));
+  }
+
+  fn testb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_testb(dst, src));
+  }
+
+  fn cmpl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpl(dst, src));
+  }
+
+  fn cmpw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpw(dst, src));
+  }
+
+  fn cmpb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_cmpb(dst, src));
   }
 }
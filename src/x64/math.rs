@@ -1,6 +1,94 @@
 use asm::*;
 use asm::x64::base::*;
 
+fn unary<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                    name: &'static str,
+                                    op: u8,
+                                    digit: u8,
+                                    size: OperandSize,
+                                    dst: Operand) -> AsmResult {
+  if !dst.is_rm() {
+    return invalid(name, &[dst], "expected register or memory");
+  }
+  m.emit_sized_rex(size, Empty, dst);
+  m.emitb(size.opcode(op));
+  m.emit_modrm(_Operation(digit), dst);
+  Ok(())
+}
+
+fn shift<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                    name: &'static str,
+                                    digit: u8,
+                                    size: OperandSize,
+                                    dst: Operand,
+                                    src: Operand) -> AsmResult {
+  match (dst, src) {
+    (_, Byte(1)) if dst.is_rm() => {
+      m.emit_sized_rex(size, Empty, dst);
+      m.emitb(size.opcode(0xd0));
+      m.emit_modrm(_Operation(digit), dst);
+    },
+    (_, Byte(b)) if dst.is_rm() => {
+      m.emit_sized_rex(size, Empty, dst);
+      m.emitb(size.opcode(0xc0));
+      m.emit_modrm_imm(_Operation(digit), dst, Byte(b));
+    },
+    (_, R(rcx)) if dst.is_rm() => {
+      m.emit_sized_rex(size, Empty, dst);
+      m.emitb(size.opcode(0xd2));
+      m.emit_modrm(_Operation(digit), dst);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+fn bit_test<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                       name: &'static str,
+                                       digit: u8,
+                                       dst: Operand,
+                                       src: Operand) -> AsmResult {
+  match (dst, src) {
+    (_, R(_)) if dst.is_rm() => {
+      m.emit_rex(REXW, src, dst);
+      m.emitb(0x0f);
+      m.emitb(0xa3 | ((digit - 4) << 3));
+      m.emit_modrm(src, dst);
+    },
+    (_, Byte(b)) if dst.is_rm() => {
+      m.emit_rex(REXW, Empty, dst);
+      m.emitb(0x0f);
+      m.emitb(0xba);
+      m.emit_modrm_imm(_Operation(digit), dst, Byte(b));
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+fn bit_scan<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                       name: &'static str,
+                                       prefix: Option<u8>,
+                                       op: u8,
+                                       dst: Operand,
+                                       src: Operand) -> AsmResult {
+  match (dst, src) {
+    (R(_), _) if src.is_rm() => {
+      // Mandatory prefix goes before REX
+      match prefix {
+        Some(p) => m.emitb(p),
+        None => ()
+      }
+      m.emit_rex(REXW, dst, src);
+      m.emitb(0x0f);
+      m.emitb(op);
+      m.emit_modrm(dst, src);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
 pub trait AsmX64Math {
   fn incq(&mut self, dst: Operand);
   fn decq(&mut self, dst: Operand);
@@ -16,248 +104,1049 @@ pub trait AsmX64Math {
   fn andq(&mut self, dst: Operand, src: Operand);
   fn orq(&mut self, dst: Operand, src: Operand);
   fn xorq(&mut self, dst: Operand, src: Operand);
+  fn incl(&mut self, dst: Operand);
+  fn incw(&mut self, dst: Operand);
+  fn incb(&mut self, dst: Operand);
+  fn decl(&mut self, dst: Operand);
+  fn decw(&mut self, dst: Operand);
+  fn decb(&mut self, dst: Operand);
+  fn addl(&mut self, dst: Operand, src: Operand);
+  fn addw(&mut self, dst: Operand, src: Operand);
+  fn addb(&mut self, dst: Operand, src: Operand);
+  fn subl(&mut self, dst: Operand, src: Operand);
+  fn subw(&mut self, dst: Operand, src: Operand);
+  fn subb(&mut self, dst: Operand, src: Operand);
+  fn andl(&mut self, dst: Operand, src: Operand);
+  fn andw(&mut self, dst: Operand, src: Operand);
+  fn andb(&mut self, dst: Operand, src: Operand);
+  fn orl(&mut self, dst: Operand, src: Operand);
+  fn orw(&mut self, dst: Operand, src: Operand);
+  fn orb(&mut self, dst: Operand, src: Operand);
+  fn xorl(&mut self, dst: Operand, src: Operand);
+  fn xorw(&mut self, dst: Operand, src: Operand);
+  fn xorb(&mut self, dst: Operand, src: Operand);
+  fn shll(&mut self, dst: Operand, src: Operand);
+  fn shlw(&mut self, dst: Operand, src: Operand);
+  fn shlb(&mut self, dst: Operand, src: Operand);
+  fn shrl(&mut self, dst: Operand, src: Operand);
+  fn shrw(&mut self, dst: Operand, src: Operand);
+  fn shrb(&mut self, dst: Operand, src: Operand);
+  fn sarl(&mut self, dst: Operand, src: Operand);
+  fn sarw(&mut self, dst: Operand, src: Operand);
+  fn sarb(&mut self, dst: Operand, src: Operand);
+  fn divl(&mut self, src: Operand);
+  fn divw(&mut self, src: Operand);
+  fn divb(&mut self, src: Operand);
+  fn mull(&mut self, src: Operand);
+  fn mulw(&mut self, src: Operand);
+  fn mulb(&mut self, src: Operand);
+  fn idivl(&mut self, src: Operand);
+  fn idivw(&mut self, src: Operand);
+  fn idivb(&mut self, src: Operand);
+  fn imull(&mut self, src: Operand);
+  fn imulw(&mut self, src: Operand);
+  fn imulb(&mut self, src: Operand);
+  fn rolq(&mut self, dst: Operand, src: Operand);
+  fn rorq(&mut self, dst: Operand, src: Operand);
+  fn rclq(&mut self, dst: Operand, src: Operand);
+  fn rcrq(&mut self, dst: Operand, src: Operand);
+  fn roll(&mut self, dst: Operand, src: Operand);
+  fn rolw(&mut self, dst: Operand, src: Operand);
+  fn rolb(&mut self, dst: Operand, src: Operand);
+  fn rorl(&mut self, dst: Operand, src: Operand);
+  fn rorw(&mut self, dst: Operand, src: Operand);
+  fn rorb(&mut self, dst: Operand, src: Operand);
+  fn rcll(&mut self, dst: Operand, src: Operand);
+  fn rclw(&mut self, dst: Operand, src: Operand);
+  fn rclb(&mut self, dst: Operand, src: Operand);
+  fn rcrl(&mut self, dst: Operand, src: Operand);
+  fn rcrw(&mut self, dst: Operand, src: Operand);
+  fn rcrb(&mut self, dst: Operand, src: Operand);
+  fn imulq2(&mut self, dst: Operand, src: Operand);
+  fn imulq3(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn cqo(&mut self);
+  fn cdq(&mut self);
+  fn cwd(&mut self);
+  fn cdqe(&mut self);
+  fn cwde(&mut self);
+  fn cbw(&mut self);
+  fn adcq(&mut self, dst: Operand, src: Operand);
+  fn sbbq(&mut self, dst: Operand, src: Operand);
+  fn negq(&mut self, dst: Operand);
+  fn notq(&mut self, dst: Operand);
+  fn negl(&mut self, dst: Operand);
+  fn negw(&mut self, dst: Operand);
+  fn negb(&mut self, dst: Operand);
+  fn notl(&mut self, dst: Operand);
+  fn notw(&mut self, dst: Operand);
+  fn notb(&mut self, dst: Operand);
+  fn adcl(&mut self, dst: Operand, src: Operand);
+  fn adcw(&mut self, dst: Operand, src: Operand);
+  fn adcb(&mut self, dst: Operand, src: Operand);
+  fn sbbl(&mut self, dst: Operand, src: Operand);
+  fn sbbw(&mut self, dst: Operand, src: Operand);
+  fn sbbb(&mut self, dst: Operand, src: Operand);
+  fn btq(&mut self, dst: Operand, src: Operand);
+  fn btsq(&mut self, dst: Operand, src: Operand);
+  fn btrq(&mut self, dst: Operand, src: Operand);
+  fn btcq(&mut self, dst: Operand, src: Operand);
+  fn bsfq(&mut self, dst: Operand, src: Operand);
+  fn bsrq(&mut self, dst: Operand, src: Operand);
+  fn popcntq(&mut self, dst: Operand, src: Operand);
+  fn lzcntq(&mut self, dst: Operand, src: Operand);
+  fn tzcntq(&mut self, dst: Operand, src: Operand);
 }
 
-impl<A: AsmBuffer+AsmX64Helper> AsmX64Math for A {
-  fn incq(&mut self, dst: Operand) {
-    self.emit_rex(REXW, Empty, dst);
-    self.emitb(0xff);
-    self.emit_modrm(_Operation(0), dst);
+pub trait AsmX64MathTry {
+  fn try_incq(&mut self, dst: Operand) -> AsmResult;
+  fn try_decq(&mut self, dst: Operand) -> AsmResult;
+  fn try_addq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divq(&mut self, src: Operand) -> AsmResult;
+  fn try_mulq(&mut self, src: Operand) -> AsmResult;
+  fn try_idivq(&mut self, src: Operand) -> AsmResult;
+  fn try_imulq(&mut self, src: Operand) -> AsmResult;
+  fn try_shlq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shrq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sarq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_incl(&mut self, dst: Operand) -> AsmResult;
+  fn try_incw(&mut self, dst: Operand) -> AsmResult;
+  fn try_incb(&mut self, dst: Operand) -> AsmResult;
+  fn try_decl(&mut self, dst: Operand) -> AsmResult;
+  fn try_decw(&mut self, dst: Operand) -> AsmResult;
+  fn try_decb(&mut self, dst: Operand) -> AsmResult;
+  fn try_addl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_addb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_subb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_andb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_orb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_xorb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shll(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shlw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shlb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shrl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shrw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_shrb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sarl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sarw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sarb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_divl(&mut self, src: Operand) -> AsmResult;
+  fn try_divw(&mut self, src: Operand) -> AsmResult;
+  fn try_divb(&mut self, src: Operand) -> AsmResult;
+  fn try_mull(&mut self, src: Operand) -> AsmResult;
+  fn try_mulw(&mut self, src: Operand) -> AsmResult;
+  fn try_mulb(&mut self, src: Operand) -> AsmResult;
+  fn try_idivl(&mut self, src: Operand) -> AsmResult;
+  fn try_idivw(&mut self, src: Operand) -> AsmResult;
+  fn try_idivb(&mut self, src: Operand) -> AsmResult;
+  fn try_imull(&mut self, src: Operand) -> AsmResult;
+  fn try_imulw(&mut self, src: Operand) -> AsmResult;
+  fn try_imulb(&mut self, src: Operand) -> AsmResult;
+  fn try_rolq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rorq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rclq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcrq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_roll(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rolw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rolb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rorl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rorw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rorb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcll(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rclw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rclb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcrl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcrw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_rcrb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_imulq2(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_imulq3(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_adcq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_negq(&mut self, dst: Operand) -> AsmResult;
+  fn try_notq(&mut self, dst: Operand) -> AsmResult;
+  fn try_negl(&mut self, dst: Operand) -> AsmResult;
+  fn try_negw(&mut self, dst: Operand) -> AsmResult;
+  fn try_negb(&mut self, dst: Operand) -> AsmResult;
+  fn try_notl(&mut self, dst: Operand) -> AsmResult;
+  fn try_notw(&mut self, dst: Operand) -> AsmResult;
+  fn try_notb(&mut self, dst: Operand) -> AsmResult;
+  fn try_adcl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_adcw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_adcb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbl(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_sbbb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btsq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btrq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_btcq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_bsfq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_bsrq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_popcntq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_lzcntq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_tzcntq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64MathTry for A {
+  fn try_incq(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incq", 0xfe, 0, Size64, dst)
   }
 
-  fn decq(&mut self, dst: Operand) {
-    self.emit_rex(REXW, Empty, dst);
-    self.emitb(0xff);
-    self.emit_modrm(_Operation(1), dst);
+  fn try_decq(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decq", 0xfe, 1, Size64, dst)
   }
 
-  fn addq(&mut self, dst: Operand, src: Operand) {
+  fn try_addq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size64, dst, src)
+  }
+
+  fn try_subq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size64, dst, src)
+  }
+
+  fn try_divq(&mut self, src: Operand) -> AsmResult {
+    unary(self, "divq", 0xf6, 6, Size64, src)
+  }
+
+  fn try_mulq(&mut self, src: Operand) -> AsmResult {
+    unary(self, "mulq", 0xf6, 4, Size64, src)
+  }
+
+  fn try_idivq(&mut self, src: Operand) -> AsmResult {
+    unary(self, "idivq", 0xf6, 7, Size64, src)
+  }
+
+  fn try_imulq(&mut self, src: Operand) -> AsmResult {
+    unary(self, "imulq", 0xf6, 5, Size64, src)
+  }
+
+  fn try_shlq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shlq", 4, Size64, dst, src)
+  }
+
+  fn try_shrq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shrq", 5, Size64, dst, src)
+  }
+
+  fn try_sarq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "sarq", 7, Size64, dst, src)
+  }
+
+  fn try_andq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size64, dst, src)
+  }
+
+  fn try_orq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size64, dst, src)
+  }
+
+  fn try_xorq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size64, dst, src)
+  }
+
+  fn try_incl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incl", 0xfe, 0, Size32, dst)
+  }
+
+  fn try_incw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incw", 0xfe, 0, Size16, dst)
+  }
+
+  fn try_incb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "incb", 0xfe, 0, Size8, dst)
+  }
+
+  fn try_decl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decl", 0xfe, 1, Size32, dst)
+  }
+
+  fn try_decw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decw", 0xfe, 1, Size16, dst)
+  }
+
+  fn try_decb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "decb", 0xfe, 1, Size8, dst)
+  }
+
+  fn try_addl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size32, dst, src)
+  }
+
+  fn try_addw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size16, dst, src)
+  }
+
+  fn try_addb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdd, Size8, dst, src)
+  }
+
+  fn try_subl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size32, dst, src)
+  }
+
+  fn try_subw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size16, dst, src)
+  }
+
+  fn try_subb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSub, Size8, dst, src)
+  }
+
+  fn try_andl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size32, dst, src)
+  }
+
+  fn try_andw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size16, dst, src)
+  }
+
+  fn try_andb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAnd, Size8, dst, src)
+  }
+
+  fn try_orl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size32, dst, src)
+  }
+
+  fn try_orw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size16, dst, src)
+  }
+
+  fn try_orb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluOr, Size8, dst, src)
+  }
+
+  fn try_xorl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size32, dst, src)
+  }
+
+  fn try_xorw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size16, dst, src)
+  }
+
+  fn try_xorb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluXor, Size8, dst, src)
+  }
+
+  fn try_shll(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shll", 4, Size32, dst, src)
+  }
+
+  fn try_shlw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shlw", 4, Size16, dst, src)
+  }
+
+  fn try_shlb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shlb", 4, Size8, dst, src)
+  }
+
+  fn try_shrl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shrl", 5, Size32, dst, src)
+  }
+
+  fn try_shrw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shrw", 5, Size16, dst, src)
+  }
+
+  fn try_shrb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "shrb", 5, Size8, dst, src)
+  }
+
+  fn try_sarl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "sarl", 7, Size32, dst, src)
+  }
+
+  fn try_sarw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "sarw", 7, Size16, dst, src)
+  }
+
+  fn try_sarb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "sarb", 7, Size8, dst, src)
+  }
+
+  fn try_divl(&mut self, src: Operand) -> AsmResult {
+    unary(self, "divl", 0xf6, 6, Size32, src)
+  }
+
+  fn try_divw(&mut self, src: Operand) -> AsmResult {
+    unary(self, "divw", 0xf6, 6, Size16, src)
+  }
+
+  fn try_divb(&mut self, src: Operand) -> AsmResult {
+    unary(self, "divb", 0xf6, 6, Size8, src)
+  }
+
+  fn try_mull(&mut self, src: Operand) -> AsmResult {
+    unary(self, "mull", 0xf6, 4, Size32, src)
+  }
+
+  fn try_mulw(&mut self, src: Operand) -> AsmResult {
+    unary(self, "mulw", 0xf6, 4, Size16, src)
+  }
+
+  fn try_mulb(&mut self, src: Operand) -> AsmResult {
+    unary(self, "mulb", 0xf6, 4, Size8, src)
+  }
+
+  fn try_idivl(&mut self, src: Operand) -> AsmResult {
+    unary(self, "idivl", 0xf6, 7, Size32, src)
+  }
+
+  fn try_idivw(&mut self, src: Operand) -> AsmResult {
+    unary(self, "idivw", 0xf6, 7, Size16, src)
+  }
+
+  fn try_idivb(&mut self, src: Operand) -> AsmResult {
+    unary(self, "idivb", 0xf6, 7, Size8, src)
+  }
+
+  fn try_imull(&mut self, src: Operand) -> AsmResult {
+    unary(self, "imull", 0xf6, 5, Size32, src)
+  }
+
+  fn try_imulw(&mut self, src: Operand) -> AsmResult {
+    unary(self, "imulw", 0xf6, 5, Size16, src)
+  }
+
+  fn try_imulb(&mut self, src: Operand) -> AsmResult {
+    unary(self, "imulb", 0xf6, 5, Size8, src)
+  }
+
+  fn try_rolq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rolq", 0, Size64, dst, src)
+  }
+
+  fn try_rorq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rorq", 1, Size64, dst, src)
+  }
+
+  fn try_rclq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rclq", 2, Size64, dst, src)
+  }
+
+  fn try_rcrq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcrq", 3, Size64, dst, src)
+  }
+
+  fn try_roll(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "roll", 0, Size32, dst, src)
+  }
+
+  fn try_rolw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rolw", 0, Size16, dst, src)
+  }
+
+  fn try_rolb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rolb", 0, Size8, dst, src)
+  }
+
+  fn try_rorl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rorl", 1, Size32, dst, src)
+  }
+
+  fn try_rorw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rorw", 1, Size16, dst, src)
+  }
+
+  fn try_rorb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rorb", 1, Size8, dst, src)
+  }
+
+  fn try_rcll(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcll", 2, Size32, dst, src)
+  }
+
+  fn try_rclw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rclw", 2, Size16, dst, src)
+  }
+
+  fn try_rclb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rclb", 2, Size8, dst, src)
+  }
+
+  fn try_rcrl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcrl", 3, Size32, dst, src)
+  }
+
+  fn try_rcrw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcrw", 3, Size16, dst, src)
+  }
+
+  fn try_rcrb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "rcrb", 3, Size8, dst, src)
+  }
+
+  fn try_imulq2(&mut self, dst: Operand, src: Operand) -> AsmResult {
     match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0x05);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(0), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(0), dst);
-        self.emitl(l);
-      },
       (R(_), _) if src.is_rm() => {
         self.emit_rex(REXW, dst, src);
-        self.emitb(0x03);
+        self.emitb(0x0f);
+        self.emitb(0xaf);
         self.emit_modrm(dst, src);
       },
-      (_, R(_)) if dst.is_rm() => {
-        self.emit_rex(REXW, src, dst);
-        self.emitb(0x01);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
+      _ => return invalid("imulq2", &[dst, src], "unsupported operands")
     }
+    Ok(())
   }
 
-  fn subq(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0x2d);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(5), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(5), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
+  fn try_imulq3(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (R(_), _, Byte(_)) if src.is_rm() => {
         self.emit_rex(REXW, dst, src);
-        self.emitb(0x2b);
-        self.emit_modrm(dst, src);
+        self.emitb(0x6b);
+        self.emit_modrm_imm(dst, src, imm);
       },
-      (_, R(_)) if dst.is_rm() => {
-        fail!("You can only substract r32 from rm");
+      (R(_), _, Long(_)) if src.is_rm() => {
+        self.emit_rex(REXW, dst, src);
+        self.emitb(0x69);
+        self.emit_modrm_imm(dst, src, imm);
       },
-      _ => fail!()
+      _ => return invalid("imulq3", &[dst, src, imm], "unsupported operands")
     }
+    Ok(())
+  }
+
+  fn try_adcq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size64, dst, src)
+  }
+
+  fn try_sbbq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size64, dst, src)
+  }
+
+  fn try_negq(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negq", 0xf6, 3, Size64, dst)
+  }
+
+  fn try_notq(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notq", 0xf6, 2, Size64, dst)
+  }
+
+  fn try_negl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negl", 0xf6, 3, Size32, dst)
+  }
+
+  fn try_negw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negw", 0xf6, 3, Size16, dst)
+  }
+
+  fn try_negb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "negb", 0xf6, 3, Size8, dst)
+  }
+
+  fn try_notl(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notl", 0xf6, 2, Size32, dst)
+  }
+
+  fn try_notw(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notw", 0xf6, 2, Size16, dst)
+  }
+
+  fn try_notb(&mut self, dst: Operand) -> AsmResult {
+    unary(self, "notb", 0xf6, 2, Size8, dst)
+  }
+
+  fn try_adcl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size32, dst, src)
+  }
+
+  fn try_adcw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size16, dst, src)
+  }
+
+  fn try_adcb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluAdc, Size8, dst, src)
+  }
+
+  fn try_sbbl(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size32, dst, src)
+  }
+
+  fn try_sbbw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size16, dst, src)
+  }
+
+  fn try_sbbb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    self.emit_alu(AluSbb, Size8, dst, src)
+  }
+
+  fn try_btq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btq", 4, dst, src)
+  }
+
+  fn try_btsq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btsq", 5, dst, src)
+  }
+
+  fn try_btrq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btrq", 6, dst, src)
+  }
+
+  fn try_btcq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_test(self, "btcq", 7, dst, src)
+  }
+
+  fn try_bsfq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "bsfq", None, 0xbc, dst, src)
+  }
+
+  fn try_bsrq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "bsrq", None, 0xbd, dst, src)
+  }
+
+  fn try_popcntq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "popcntq", Some(0xf3), 0xb8, dst, src)
+  }
+
+  fn try_lzcntq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "lzcntq", Some(0xf3), 0xbd, dst, src)
+  }
+
+  fn try_tzcntq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    bit_scan(self, "tzcntq", Some(0xf3), 0xbc, dst, src)
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64MathTry> AsmX64Math for A {
+  fn incq(&mut self, dst: Operand) {
+    or_fail(self.try_incq(dst));
+  }
+
+  fn decq(&mut self, dst: Operand) {
+    or_fail(self.try_decq(dst));
+  }
+
+  fn addq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addq(dst, src));
+  }
+
+  fn subq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subq(dst, src));
   }
 
   fn divq(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emit_rex(REXW, Empty, src);
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(6), src);
+    or_fail(self.try_divq(src));
   }
 
   fn mulq(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emit_rex(REXW, Empty, src);
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(4), src);
+    or_fail(self.try_mulq(src));
   }
 
   fn idivq(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emit_rex(REXW, Empty, src);
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(7), src);
+    or_fail(self.try_idivq(src));
   }
 
   fn imulq(&mut self, src: Operand) {
-    assert!(src.is_rm());
-    self.emit_rex(REXW, Empty, src);
-    self.emitb(0xf7);
-    self.emit_modrm(_Operation(5), src);
+    or_fail(self.try_imulq(src));
   }
 
   fn shlq(&mut self, dst: Operand, src: Operand) {
-    assert!(dst.is_rm());
-    match src {
-      Byte(b) => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0xc1);
-        self.emit_modrm(_Operation(4), dst);
-        self.emitb(b);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_shlq(dst, src));
   }
 
   fn shrq(&mut self, dst: Operand, src: Operand) {
-    assert!(dst.is_rm());
-    match src {
-      Byte(b) => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0xc1);
-        self.emit_modrm(_Operation(5), dst);
-        self.emitb(b);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_shrq(dst, src));
   }
 
   fn sarq(&mut self, dst: Operand, src: Operand) {
-    assert!(dst.is_rm());
-    match src {
-      Byte(b) => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0xc1);
-        self.emit_modrm(_Operation(7), dst);
-        self.emitb(b);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_sarq(dst, src));
   }
 
   fn andq(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0x25);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(4), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(4), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emit_rex(REXW, dst, src);
-        self.emitb(0x23);
-        self.emit_modrm(dst, src);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emit_rex(REXW, src, dst);
-        self.emitb(0x21);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_andq(dst, src));
   }
 
   fn orq(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0x0d);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(1), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(1), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emit_rex(REXW, dst, src);
-        self.emitb(0x0b);
-        self.emit_modrm(dst, src);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emit_rex(REXW, src, dst);
-        self.emitb(0x0a);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_orq(dst, src));
   }
 
   fn xorq(&mut self, dst: Operand, src: Operand) {
-    match (dst, src) {
-      (R(rax), Long(l)) => {
-        self.emit_rex(REXW, Empty, Empty);
-        self.emitb(0x35);
-        self.emitl(l);
-      },
-      (_, Byte(b)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x83);
-        self.emit_modrm(_Operation(6), dst);
-        self.emitb(b);
-      },
-      (_, Long(l)) if dst.is_rm() => {
-        self.emit_rex(REXW, Empty, dst);
-        self.emitb(0x81);
-        self.emit_modrm(_Operation(6), dst);
-        self.emitl(l);
-      },
-      (R(_), _) if src.is_rm() => {
-        self.emit_rex(REXW, dst, src);
-        self.emitb(0x33);
-        self.emit_modrm(dst, src);
-      },
-      (_, R(_)) if dst.is_rm() => {
-        self.emit_rex(REXW, src, dst);
-        self.emitb(0x31);
-        self.emit_modrm(src, dst);
-      },
-      _ => fail!()
-    }
+    or_fail(self.try_xorq(dst, src));
+  }
+
+  fn incl(&mut self, dst: Operand) {
+    or_fail(self.try_incl(dst));
+  }
+
+  fn incw(&mut self, dst: Operand) {
+    or_fail(self.try_incw(dst));
+  }
+
+  fn incb(&mut self, dst: Operand) {
+    or_fail(self.try_incb(dst));
+  }
+
+  fn decl(&mut self, dst: Operand) {
+    or_fail(self.try_decl(dst));
+  }
+
+  fn decw(&mut self, dst: Operand) {
+    or_fail(self.try_decw(dst));
+  }
+
+  fn decb(&mut self, dst: Operand) {
+    or_fail(self.try_decb(dst));
+  }
+
+  fn addl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addl(dst, src));
+  }
+
+  fn addw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addw(dst, src));
+  }
+
+  fn addb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_addb(dst, src));
+  }
+
+  fn subl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subl(dst, src));
+  }
+
+  fn subw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subw(dst, src));
+  }
+
+  fn subb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_subb(dst, src));
+  }
+
+  fn andl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_andl(dst, src));
+  }
+
+  fn andw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_andw(dst, src));
+  }
+
+  fn andb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_andb(dst, src));
+  }
+
+  fn orl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_orl(dst, src));
+  }
+
+  fn orw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_orw(dst, src));
+  }
+
+  fn orb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_orb(dst, src));
+  }
+
+  fn xorl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xorl(dst, src));
+  }
+
+  fn xorw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xorw(dst, src));
+  }
+
+  fn xorb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_xorb(dst, src));
+  }
+
+  fn shll(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_shll(dst, src));
+  }
+
+  fn shlw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_shlw(dst, src));
+  }
+
+  fn shlb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_shlb(dst, src));
+  }
+
+  fn shrl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_shrl(dst, src));
+  }
+
+  fn shrw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_shrw(dst, src));
+  }
+
+  fn shrb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_shrb(dst, src));
+  }
+
+  fn sarl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sarl(dst, src));
+  }
+
+  fn sarw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sarw(dst, src));
+  }
+
+  fn sarb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sarb(dst, src));
+  }
+
+  fn divl(&mut self, src: Operand) {
+    or_fail(self.try_divl(src));
+  }
+
+  fn divw(&mut self, src: Operand) {
+    or_fail(self.try_divw(src));
+  }
+
+  fn divb(&mut self, src: Operand) {
+    or_fail(self.try_divb(src));
+  }
+
+  fn mull(&mut self, src: Operand) {
+    or_fail(self.try_mull(src));
+  }
+
+  fn mulw(&mut self, src: Operand) {
+    or_fail(self.try_mulw(src));
+  }
+
+  fn mulb(&mut self, src: Operand) {
+    or_fail(self.try_mulb(src));
+  }
+
+  fn idivl(&mut self, src: Operand) {
+    or_fail(self.try_idivl(src));
+  }
+
+  fn idivw(&mut self, src: Operand) {
+    or_fail(self.try_idivw(src));
+  }
+
+  fn idivb(&mut self, src: Operand) {
+    or_fail(self.try_idivb(src));
+  }
+
+  fn imull(&mut self, src: Operand) {
+    or_fail(self.try_imull(src));
+  }
+
+  fn imulw(&mut self, src: Operand) {
+    or_fail(self.try_imulw(src));
+  }
+
+  fn imulb(&mut self, src: Operand) {
+    or_fail(self.try_imulb(src));
+  }
+
+  fn rolq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rolq(dst, src));
+  }
+
+  fn rorq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rorq(dst, src));
+  }
+
+  fn rclq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rclq(dst, src));
+  }
+
+  fn rcrq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcrq(dst, src));
+  }
+
+  fn roll(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_roll(dst, src));
+  }
+
+  fn rolw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rolw(dst, src));
+  }
+
+  fn rolb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rolb(dst, src));
+  }
+
+  fn rorl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rorl(dst, src));
+  }
+
+  fn rorw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rorw(dst, src));
+  }
+
+  fn rorb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rorb(dst, src));
+  }
+
+  fn rcll(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcll(dst, src));
+  }
+
+  fn rclw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rclw(dst, src));
+  }
+
+  fn rclb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rclb(dst, src));
+  }
+
+  fn rcrl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcrl(dst, src));
+  }
+
+  fn rcrw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcrw(dst, src));
+  }
+
+  fn rcrb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_rcrb(dst, src));
+  }
+
+  fn imulq2(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_imulq2(dst, src));
+  }
+
+  fn imulq3(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_imulq3(dst, src, imm));
+  }
+
+  fn cqo(&mut self) {
+    self.emitb(0x48);
+    self.emitb(0x99);
+  }
+
+  fn cdq(&mut self) {
+    self.emitb(0x99);
+  }
+
+  fn cwd(&mut self) {
+    self.emitb(0x66);
+    self.emitb(0x99);
+  }
+
+  fn cdqe(&mut self) {
+    self.emitb(0x48);
+    self.emitb(0x98);
+  }
+
+  fn cwde(&mut self) {
+    self.emitb(0x98);
+  }
+
+  fn cbw(&mut self) {
+    self.emitb(0x66);
+    self.emitb(0x98);
+  }
+
+  fn adcq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcq(dst, src));
+  }
+
+  fn sbbq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbq(dst, src));
+  }
+
+  fn negq(&mut self, dst: Operand) {
+    or_fail(self.try_negq(dst));
+  }
+
+  fn notq(&mut self, dst: Operand) {
+    or_fail(self.try_notq(dst));
+  }
+
+  fn negl(&mut self, dst: Operand) {
+    or_fail(self.try_negl(dst));
+  }
+
+  fn negw(&mut self, dst: Operand) {
+    or_fail(self.try_negw(dst));
+  }
+
+  fn negb(&mut self, dst: Operand) {
+    or_fail(self.try_negb(dst));
+  }
+
+  fn notl(&mut self, dst: Operand) {
+    or_fail(self.try_notl(dst));
+  }
+
+  fn notw(&mut self, dst: Operand) {
+    or_fail(self.try_notw(dst));
+  }
+
+  fn notb(&mut self, dst: Operand) {
+    or_fail(self.try_notb(dst));
+  }
+
+  fn adcl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcl(dst, src));
+  }
+
+  fn adcw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcw(dst, src));
+  }
+
+  fn adcb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_adcb(dst, src));
+  }
+
+  fn sbbl(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbl(dst, src));
+  }
+
+  fn sbbw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbw(dst, src));
+  }
+
+  fn sbbb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_sbbb(dst, src));
+  }
+
+  fn btq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btq(dst, src));
+  }
+
+  fn btsq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btsq(dst, src));
+  }
+
+  fn btrq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btrq(dst, src));
+  }
+
+  fn btcq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_btcq(dst, src));
+  }
+
+  fn bsfq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_bsfq(dst, src));
+  }
+
+  fn bsrq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_bsrq(dst, src));
+  }
+
+  fn popcntq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_popcntq(dst, src));
+  }
+
+  fn lzcntq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_lzcntq(dst, src));
+  }
+
+  fn tzcntq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_tzcntq(dst, src));
   }
 }
@@ -0,0 +1,684 @@
+use asm::*;
+use asm::x64::base::*;
+
+// xmm = op(xmm, xmm/m)
+fn simd<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                   name: &'static str,
+                                   escape: Option<u8>,
+                                   op: u8,
+                                   dst: Operand,
+                                   src: Operand) -> AsmResult {
+  match (dst, src) {
+    (D(_), _) if src.is_dm() => {
+      m.emit_sse(false, Some(0x66), escape, op, dst, src, Empty);
+    },
+    _ => return invalid(name, &[dst, src], "unsupported operands")
+  }
+  Ok(())
+}
+
+// Shift by xmm/m or by an immediate, the latter uses a /digit form
+fn shift<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                    name: &'static str,
+                                    op: u8,
+                                    imm_op: u8,
+                                    digit: u8,
+                                    dst: Operand,
+                                    src: Operand) -> AsmResult {
+  match (dst, src) {
+    (D(_), Byte(_)) => {
+      m.emit_sse(false, Some(0x66), None, imm_op, _Operation(digit), dst, src);
+    },
+    _ => return simd(m, name, None, op, dst, src)
+  }
+  Ok(())
+}
+
+// xmm = op(xmm, xmm/m, imm8)
+fn simd_imm<A: AsmBuffer+AsmX64Helper>(m: &mut A,
+                                       name: &'static str,
+                                       escape: Option<u8>,
+                                       op: u8,
+                                       dst: Operand,
+                                       src: Operand,
+                                       imm: Operand) -> AsmResult {
+  match (dst, src, imm) {
+    (D(_), _, Byte(_)) if src.is_dm() => {
+      m.emit_sse(false, Some(0x66), escape, op, dst, src, imm);
+    },
+    _ => return invalid(name, &[dst, src, imm], "unsupported operands")
+  }
+  Ok(())
+}
+
+pub trait AsmX64Simd {
+  // Moves
+  fn movdqa(&mut self, dst: Operand, src: Operand);
+  fn movdqu(&mut self, dst: Operand, src: Operand);
+
+  // Arithmetic
+  fn paddb(&mut self, dst: Operand, src: Operand);
+  fn paddw(&mut self, dst: Operand, src: Operand);
+  fn paddd(&mut self, dst: Operand, src: Operand);
+  fn paddq(&mut self, dst: Operand, src: Operand);
+  fn psubb(&mut self, dst: Operand, src: Operand);
+  fn psubw(&mut self, dst: Operand, src: Operand);
+  fn psubd(&mut self, dst: Operand, src: Operand);
+  fn psubq(&mut self, dst: Operand, src: Operand);
+  fn pmullw(&mut self, dst: Operand, src: Operand);
+  fn pmulld(&mut self, dst: Operand, src: Operand);
+  fn pmuludq(&mut self, dst: Operand, src: Operand);
+  fn pminsd(&mut self, dst: Operand, src: Operand);
+  fn pmaxsd(&mut self, dst: Operand, src: Operand);
+
+  // Comparison
+  fn pcmpeqb(&mut self, dst: Operand, src: Operand);
+  fn pcmpeqw(&mut self, dst: Operand, src: Operand);
+  fn pcmpeqd(&mut self, dst: Operand, src: Operand);
+  fn pcmpeqq(&mut self, dst: Operand, src: Operand);
+  fn pcmpgtb(&mut self, dst: Operand, src: Operand);
+  fn pcmpgtw(&mut self, dst: Operand, src: Operand);
+  fn pcmpgtd(&mut self, dst: Operand, src: Operand);
+  fn pcmpgtq(&mut self, dst: Operand, src: Operand);
+  fn ptest(&mut self, dst: Operand, src: Operand);
+
+  // Logic
+  fn pand(&mut self, dst: Operand, src: Operand);
+  fn pandn(&mut self, dst: Operand, src: Operand);
+  fn por(&mut self, dst: Operand, src: Operand);
+  fn pxor(&mut self, dst: Operand, src: Operand);
+
+  // Shifts
+  fn psllw(&mut self, dst: Operand, src: Operand);
+  fn pslld(&mut self, dst: Operand, src: Operand);
+  fn psllq(&mut self, dst: Operand, src: Operand);
+  fn psrlw(&mut self, dst: Operand, src: Operand);
+  fn psrld(&mut self, dst: Operand, src: Operand);
+  fn psrlq(&mut self, dst: Operand, src: Operand);
+  fn psraw(&mut self, dst: Operand, src: Operand);
+  fn psrad(&mut self, dst: Operand, src: Operand);
+
+  // Shuffles
+  fn pshufd(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pshufb(&mut self, dst: Operand, src: Operand);
+
+  // Lanes
+  fn pinsrb(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pinsrw(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pinsrd(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pinsrq(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pextrb(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pextrw(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pextrd(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pextrq(&mut self, dst: Operand, src: Operand, imm: Operand);
+  fn pmovmskb(&mut self, dst: Operand, src: Operand);
+}
+
+pub trait AsmX64SimdTry {
+  // Moves
+  fn try_movdqa(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_movdqu(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Arithmetic
+  fn try_paddb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_paddw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_paddd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_paddq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psubb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psubw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psubd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psubq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pmullw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pmulld(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pmuludq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pminsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pmaxsd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Comparison
+  fn try_pcmpeqb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpeqw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpeqd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpeqq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpgtb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpgtw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpgtd(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pcmpgtq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_ptest(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Logic
+  fn try_pand(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pandn(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_por(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pxor(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Shifts
+  fn try_psllw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_pslld(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psllq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psrlw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psrld(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psrlq(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psraw(&mut self, dst: Operand, src: Operand) -> AsmResult;
+  fn try_psrad(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Shuffles
+  fn try_pshufd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pshufb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+
+  // Lanes
+  fn try_pinsrb(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pinsrw(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pinsrd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pinsrq(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pextrb(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pextrw(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pextrd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pextrq(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult;
+  fn try_pmovmskb(&mut self, dst: Operand, src: Operand) -> AsmResult;
+}
+
+impl<A: AsmBuffer+AsmX64Helper> AsmX64SimdTry for A {
+  fn try_movdqa(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (D(_), _) if src.is_dm() => {
+        self.emit_sse(false, Some(0x66), None, 0x6f, dst, src, Empty);
+      },
+      (_, D(_)) if dst.is_dm() => {
+        self.emit_sse(false, Some(0x66), None, 0x7f, src, dst, Empty);
+      },
+      _ => return invalid("movdqa", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_movdqu(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (D(_), _) if src.is_dm() => {
+        self.emit_sse(false, Some(0xf3), None, 0x6f, dst, src, Empty);
+      },
+      (_, D(_)) if dst.is_dm() => {
+        self.emit_sse(false, Some(0xf3), None, 0x7f, src, dst, Empty);
+      },
+      _ => return invalid("movdqu", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_paddb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "paddb", None, 0xfc, dst, src)
+  }
+
+  fn try_paddw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "paddw", None, 0xfd, dst, src)
+  }
+
+  fn try_paddd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "paddd", None, 0xfe, dst, src)
+  }
+
+  fn try_paddq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "paddq", None, 0xd4, dst, src)
+  }
+
+  fn try_psubb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "psubb", None, 0xf8, dst, src)
+  }
+
+  fn try_psubw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "psubw", None, 0xf9, dst, src)
+  }
+
+  fn try_psubd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "psubd", None, 0xfa, dst, src)
+  }
+
+  fn try_psubq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "psubq", None, 0xfb, dst, src)
+  }
+
+  fn try_pmullw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pmullw", None, 0xd5, dst, src)
+  }
+
+  fn try_pmulld(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pmulld", Some(0x38), 0x40, dst, src)
+  }
+
+  fn try_pmuludq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pmuludq", None, 0xf4, dst, src)
+  }
+
+  fn try_pminsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pminsd", Some(0x38), 0x39, dst, src)
+  }
+
+  fn try_pmaxsd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pmaxsd", Some(0x38), 0x3d, dst, src)
+  }
+
+  fn try_pcmpeqb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpeqb", None, 0x74, dst, src)
+  }
+
+  fn try_pcmpeqw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpeqw", None, 0x75, dst, src)
+  }
+
+  fn try_pcmpeqd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpeqd", None, 0x76, dst, src)
+  }
+
+  fn try_pcmpeqq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpeqq", Some(0x38), 0x29, dst, src)
+  }
+
+  fn try_pcmpgtb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpgtb", None, 0x64, dst, src)
+  }
+
+  fn try_pcmpgtw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpgtw", None, 0x65, dst, src)
+  }
+
+  fn try_pcmpgtd(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpgtd", None, 0x66, dst, src)
+  }
+
+  fn try_pcmpgtq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pcmpgtq", Some(0x38), 0x37, dst, src)
+  }
+
+  fn try_ptest(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "ptest", Some(0x38), 0x17, dst, src)
+  }
+
+  fn try_pand(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pand", None, 0xdb, dst, src)
+  }
+
+  fn try_pandn(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pandn", None, 0xdf, dst, src)
+  }
+
+  fn try_por(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "por", None, 0xeb, dst, src)
+  }
+
+  fn try_pxor(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pxor", None, 0xef, dst, src)
+  }
+
+  fn try_psllw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psllw", 0xf1, 0x71, 6, dst, src)
+  }
+
+  fn try_pslld(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "pslld", 0xf2, 0x72, 6, dst, src)
+  }
+
+  fn try_psllq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psllq", 0xf3, 0x73, 6, dst, src)
+  }
+
+  fn try_psrlw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psrlw", 0xd1, 0x71, 2, dst, src)
+  }
+
+  fn try_psrld(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psrld", 0xd2, 0x72, 2, dst, src)
+  }
+
+  fn try_psrlq(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psrlq", 0xd3, 0x73, 2, dst, src)
+  }
+
+  fn try_psraw(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psraw", 0xe1, 0x71, 4, dst, src)
+  }
+
+  fn try_psrad(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    shift(self, "psrad", 0xe2, 0x72, 4, dst, src)
+  }
+
+  fn try_pshufd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    simd_imm(self, "pshufd", None, 0x70, dst, src, imm)
+  }
+
+  fn try_pshufb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    simd(self, "pshufb", Some(0x38), 0x00, dst, src)
+  }
+
+  fn try_pinsrb(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (D(_), _, Byte(_)) if src.is_rm() => {
+        self.emit_sse(false, Some(0x66), Some(0x3a), 0x20, dst, src, imm);
+      },
+      _ => return invalid("pinsrb", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pinsrw(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (D(_), _, Byte(_)) if src.is_rm() => {
+        self.emit_sse(false, Some(0x66), None, 0xc4, dst, src, imm);
+      },
+      _ => return invalid("pinsrw", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pinsrd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (D(_), _, Byte(_)) if src.is_rm() => {
+        self.emit_sse(false, Some(0x66), Some(0x3a), 0x22, dst, src, imm);
+      },
+      _ => return invalid("pinsrd", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pinsrq(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (D(_), _, Byte(_)) if src.is_rm() => {
+        self.emit_sse(true, Some(0x66), Some(0x3a), 0x22, dst, src, imm);
+      },
+      _ => return invalid("pinsrq", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pextrb(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (_, D(_), Byte(_)) if dst.is_rm() => {
+        self.emit_sse(false, Some(0x66), Some(0x3a), 0x14, src, dst, imm);
+      },
+      _ => return invalid("pextrb", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pextrw(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (R(_), D(_), Byte(_)) => {
+        self.emit_sse(false, Some(0x66), None, 0xc5, dst, src, imm);
+      },
+      _ => return invalid("pextrw", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pextrd(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (_, D(_), Byte(_)) if dst.is_rm() => {
+        self.emit_sse(false, Some(0x66), Some(0x3a), 0x16, src, dst, imm);
+      },
+      _ => return invalid("pextrd", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pextrq(&mut self,
+                dst: Operand,
+                src: Operand,
+                imm: Operand) -> AsmResult {
+    match (dst, src, imm) {
+      (_, D(_), Byte(_)) if dst.is_rm() => {
+        self.emit_sse(true, Some(0x66), Some(0x3a), 0x16, src, dst, imm);
+      },
+      _ => return invalid("pextrq", &[dst, src, imm], "unsupported operands")
+    }
+    Ok(())
+  }
+
+  fn try_pmovmskb(&mut self, dst: Operand, src: Operand) -> AsmResult {
+    match (dst, src) {
+      (R(_), D(_)) => {
+        self.emit_sse(false, Some(0x66), None, 0xd7, dst, src, Empty);
+      },
+      _ => return invalid("pmovmskb", &[dst, src], "unsupported operands")
+    }
+    Ok(())
+  }
+}
+
+impl<A: AsmBuffer+AsmX64Helper+AsmX64SimdTry> AsmX64Simd for A {
+  fn movdqa(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movdqa(dst, src));
+  }
+
+  fn movdqu(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_movdqu(dst, src));
+  }
+
+  fn paddb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_paddb(dst, src));
+  }
+
+  fn paddw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_paddw(dst, src));
+  }
+
+  fn paddd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_paddd(dst, src));
+  }
+
+  fn paddq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_paddq(dst, src));
+  }
+
+  fn psubb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psubb(dst, src));
+  }
+
+  fn psubw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psubw(dst, src));
+  }
+
+  fn psubd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psubd(dst, src));
+  }
+
+  fn psubq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psubq(dst, src));
+  }
+
+  fn pmullw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pmullw(dst, src));
+  }
+
+  fn pmulld(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pmulld(dst, src));
+  }
+
+  fn pmuludq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pmuludq(dst, src));
+  }
+
+  fn pminsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pminsd(dst, src));
+  }
+
+  fn pmaxsd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pmaxsd(dst, src));
+  }
+
+  fn pcmpeqb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpeqb(dst, src));
+  }
+
+  fn pcmpeqw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpeqw(dst, src));
+  }
+
+  fn pcmpeqd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpeqd(dst, src));
+  }
+
+  fn pcmpeqq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpeqq(dst, src));
+  }
+
+  fn pcmpgtb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpgtb(dst, src));
+  }
+
+  fn pcmpgtw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpgtw(dst, src));
+  }
+
+  fn pcmpgtd(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpgtd(dst, src));
+  }
+
+  fn pcmpgtq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pcmpgtq(dst, src));
+  }
+
+  fn ptest(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_ptest(dst, src));
+  }
+
+  fn pand(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pand(dst, src));
+  }
+
+  fn pandn(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pandn(dst, src));
+  }
+
+  fn por(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_por(dst, src));
+  }
+
+  fn pxor(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pxor(dst, src));
+  }
+
+  fn psllw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psllw(dst, src));
+  }
+
+  fn pslld(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pslld(dst, src));
+  }
+
+  fn psllq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psllq(dst, src));
+  }
+
+  fn psrlw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psrlw(dst, src));
+  }
+
+  fn psrld(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psrld(dst, src));
+  }
+
+  fn psrlq(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psrlq(dst, src));
+  }
+
+  fn psraw(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psraw(dst, src));
+  }
+
+  fn psrad(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_psrad(dst, src));
+  }
+
+  fn pshufd(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pshufd(dst, src, imm));
+  }
+
+  fn pshufb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pshufb(dst, src));
+  }
+
+  fn pinsrb(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pinsrb(dst, src, imm));
+  }
+
+  fn pinsrw(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pinsrw(dst, src, imm));
+  }
+
+  fn pinsrd(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pinsrd(dst, src, imm));
+  }
+
+  fn pinsrq(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pinsrq(dst, src, imm));
+  }
+
+  fn pextrb(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pextrb(dst, src, imm));
+  }
+
+  fn pextrw(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pextrw(dst, src, imm));
+  }
+
+  fn pextrd(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pextrd(dst, src, imm));
+  }
+
+  fn pextrq(&mut self, dst: Operand, src: Operand, imm: Operand) {
+    or_fail(self.try_pextrq(dst, src, imm));
+  }
+
+  fn pmovmskb(&mut self, dst: Operand, src: Operand) {
+    or_fail(self.try_pmovmskb(dst, src));
+  }
+}
@@ -43,6 +43,10 @@ impl Asm {
     Asm { buffer: ~[], infos: ~[] }
   }
 
+  pub fn finalize(&mut self) -> ~[(uint, uint)] {
+    relax(&mut self.buffer, &mut self.infos)
+  }
+
   pub fn execute(&self, arg: uint) -> uint {
     let map = match os::MemoryMap::new(self.buffer.len(), ~[
       os::MapReadable,
@@ -82,7 +86,7 @@ impl Asm {
             let delta = (to as int) - (from as int) + info.nudge;
             match info.size {
               RelocByte => {
-                assert!(-127 <= delta && delta <= 128);
+                assert!(-128 <= delta && delta <= 127);
                 let p: *mut u8 = cast::transmute(map.data.offset(from));
                 *p = delta as u8;
               },
@@ -116,3 +120,10 @@ pub fn run_test(arg: uint, expected: uint, test: &fn(m: &mut Asm)) {
 
   assert!(m.execute(arg) == expected);
 }
+
+pub fn expect_bytes(expected: &[u8], test: &fn(m: &mut Asm)) {
+  let mut m = ~Asm::new();
+  test(m);
+
+  assert_eq!(m.buffer.clone(), expected.to_owned());
+}
@@ -142,3 +142,502 @@ fn fp() {
     m.ret(Empty);
   }
 }
+
+#[test]
+#[cfg(target_arch = "x86")]
+fn sib() {
+  do run_test(13589, 27178) |m| {
+    m.pushl(R(ebp));
+    m.movl(R(ebp), R(esp));
+
+    // Reserve an array of four longs on stack
+    m.subl(R(esp), Byte(16));
+    m.movl(R(edx), R(esp));
+    m.movl(R(eax), M(ebp, 8 + 4));
+
+    // a[2] = arg, a[3] = arg
+    m.movl(R(ecx), Long(2));
+    m.movl(MI(edx, ecx, Scale4, 0), R(eax));
+    m.movl(MI(edx, ecx, Scale4, 4), R(eax));
+
+    m.movl(R(eax), M(edx, 8));
+    m.addl(R(eax), M(edx, 12));
+
+    m.movl(R(esp), R(ebp));
+    m.popl(R(ebp));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn sib_encoding() {
+  do expect_bytes(&[0x8b, 0x44, 0xcb, 0x10]) |m| {
+    m.movl(R(eax), MI(ebx, ecx, Scale8, 0x10));
+  }
+  do expect_bytes(&[0x89, 0x3c, 0x96]) |m| {
+    m.movl(MI(esi, edx, Scale4, 0), R(edi));
+  }
+}
+
+#[test]
+fn special_bases() {
+  do expect_bytes(&[0x8b, 0x04, 0x24]) |m| {
+    m.movl(R(eax), M(esp, 0));
+  }
+  do expect_bytes(&[0x8b, 0x44, 0x24, 0x08]) |m| {
+    m.movl(R(eax), M(esp, 8));
+  }
+  do expect_bytes(&[0x89, 0x0c, 0x24]) |m| {
+    m.movl(M(esp, 0), R(ecx));
+  }
+  do expect_bytes(&[0x8b, 0x45, 0x00]) |m| {
+    m.movl(R(eax), M(ebp, 0));
+  }
+  do expect_bytes(&[0x8b, 0x44, 0x0d, 0x00]) |m| {
+    m.movl(R(eax), MI(ebp, ecx, Scale1, 0));
+  }
+}
+
+#[test]
+fn signed_displacement() {
+  do expect_bytes(&[0x8b, 0x45, 0xf8]) |m| {
+    m.movl(R(eax), M(ebp, -8));
+  }
+  do expect_bytes(&[0x8b, 0x85, 0x80, 0x00, 0x00, 0x00]) |m| {
+    m.movl(R(eax), M(ebp, 128));
+  }
+  do expect_bytes(&[0x8b, 0x85, 0x7f, 0xff, 0xff, 0xff]) |m| {
+    m.movl(R(eax), M(ebp, -129));
+  }
+}
+
+#[test]
+fn invalid_operands() {
+  let mut m = Asm::new();
+
+  match m.try_cvtsi2sd(R(eax), D(xmm0)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"cvtsi2sd(R, D): unsupported operands");
+    },
+    Ok(()) => fail!()
+  }
+  assert_eq!(m.buffer.len(), 0);
+}
+
+#[test]
+fn invalid_index() {
+  let mut m = Asm::new();
+
+  match m.try_movl(R(eax), MI(ebx, esp, Scale1, 0)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"movl(R, MI): unsupported operands");
+    },
+    Ok(()) => fail!()
+  }
+  assert!(m.try_pushl(MI(ebx, esp, Scale4, 8)).is_err());
+  assert!(m.try_addl(MI(ebx, esp, Scale1, 0), Byte(1)).is_err());
+
+  // Operands of the wrong kind are errors rather than failures
+  assert!(m.try_addl(R(eax), D(xmm0)).is_err());
+  assert!(m.try_movl(M(eax, 0), _Operation(0)).is_err());
+  assert_eq!(m.buffer.len(), 0);
+}
+
+#[test]
+#[cfg(target_arch = "x86")]
+fn short_jumps() {
+  do run_test(100, 400) |m| {
+    m.movl(R(eax), Long(0));
+    m.movl(R(ecx), M(esp, 8));
+
+    let mut loop_start = Label::relaxed();
+    let mut done = Label::relaxed();
+
+    m.bind(&mut loop_start);
+    m.cmpl(R(ecx), Long(0));
+    m.jccl(IfEqual, &mut done);
+
+    m.addl(R(eax), Byte(4));
+    m.decl(R(ecx));
+    m.jmpl(&mut loop_start);
+
+    m.bind(&mut done);
+    m.ret(Empty);
+    m.finalize();
+  }
+}
+
+#[test]
+fn jump_conditions() {
+  do expect_bytes(&[0x0f, 0x82, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x86, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x87, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x83, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x88, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x89, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x8a, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x8b, 0x00, 0x00, 0x00, 0x00]) |m| {
+    let mut l = Label::new();
+    m.jccl(IfBelow, &mut l);
+    m.jccl(IfBelowOrEqual, &mut l);
+    m.jccl(IfAbove, &mut l);
+    m.jccl(IfAboveOrEqual, &mut l);
+    m.jccl(IfSign, &mut l);
+    m.jccl(IfNotSign, &mut l);
+    m.jccl(IfParity, &mut l);
+    m.jccl(IfNotParity, &mut l);
+  }
+  do expect_bytes(&[0x72, 0x00, 0x73, 0x00]) |m| {
+    let mut l = Label::new();
+    m.jccb(IfCarry, &mut l);
+    m.jccb(IfNotCarry, &mut l);
+  }
+}
+
+#[test]
+fn conditional_move_encoding() {
+  do expect_bytes(&[0x0f, 0x4c, 0xc1]) |m| {
+    m.cmovl(IfLess, R(eax), R(ecx));
+  }
+  do expect_bytes(&[0x0f, 0x94, 0xc3]) |m| {
+    m.setcc(IfEqual, R(ebx));
+  }
+  do expect_bytes(&[0x0f, 0x9a, 0x46, 0x04]) |m| {
+    m.setcc(IfParity, M(esi, 4));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_setcc(IfEqual, R(esi)).is_err());
+}
+
+#[test]
+fn lea_encoding() {
+  do expect_bytes(&[0x8d, 0x44, 0x76, 0x10]) |m| {
+    m.leal(R(eax), MI(esi, esi, Scale2, 16));
+  }
+  do expect_bytes(&[0x8d, 0x4c, 0x24, 0x04]) |m| {
+    m.leal(R(ecx), M(esp, 4));
+  }
+}
+
+#[test]
+fn sign_extension_encoding() {
+  do expect_bytes(&[0x0f, 0xbe, 0xc1]) |m| {
+    m.movlsxb(R(eax), R(ecx));
+  }
+  do expect_bytes(&[0x0f, 0xbf, 0x47, 0x02]) |m| {
+    m.movlsxw(R(eax), M(edi, 2));
+  }
+
+  let mut m = Asm::new();
+  match m.try_movlsxb(R(eax), R(esi)) {
+    Err(err) => {
+      assert_eq!(err.to_str(),
+                 ~"movlsxb(R, R): no byte form of the register");
+    },
+    Ok(()) => fail!()
+  }
+  assert!(m.try_movlsxb(R(eax), R(esp)).is_err());
+  assert!(m.try_movlsxb(R(eax), M(esi, 0)).is_ok());
+}
+
+#[test]
+fn shifts_encoding() {
+  do expect_bytes(&[0xd1, 0xe0, 0xc1, 0xe8, 0x03, 0xd3, 0xf8]) |m| {
+    m.shll(R(eax), Byte(1));
+    m.shrl(R(eax), Byte(3));
+    m.sarl(R(eax), R(ecx));
+  }
+  do expect_bytes(&[0xd1, 0xc0, 0xd3, 0xc8, 0xd1, 0xd2, 0xc1, 0xdb, 0x04]) |m| {
+    m.roll(R(eax), Byte(1));
+    m.rorl(R(eax), R(ecx));
+    m.rcll(R(edx), Byte(1));
+    m.rcrl(R(ebx), Byte(4));
+  }
+}
+
+#[test]
+fn imul_encoding() {
+  do expect_bytes(&[0x0f, 0xaf, 0xc1, 0x6b, 0xc1, 0x03]) |m| {
+    m.imull2(R(eax), R(ecx));
+    m.imull3(R(eax), R(ecx), Byte(3));
+  }
+  do expect_bytes(&[0x69, 0x45, 0x08, 0x00, 0x01, 0x00, 0x00]) |m| {
+    m.imull3(R(eax), M(ebp, 8), Long(0x100));
+  }
+}
+
+#[test]
+fn sign_extension_helpers_encoding() {
+  do expect_bytes(&[0x99, 0x66, 0x99, 0x98, 0x66, 0x98]) |m| {
+    m.cdq();
+    m.cwd();
+    m.cwde();
+    m.cbw();
+  }
+}
+
+#[test]
+fn carry_arithmetic_encoding() {
+  do expect_bytes(&[0x15, 0x00, 0x01, 0x00, 0x00, 0x13, 0xc1]) |m| {
+    m.adcl(R(eax), Long(0x100));
+    m.adcl(R(eax), R(ecx));
+  }
+  do expect_bytes(&[0x83, 0xd9, 0x01, 0x19, 0x57, 0x08]) |m| {
+    m.sbbl(R(ecx), Byte(1));
+    m.sbbl(M(edi, 8), R(edx));
+  }
+  do expect_bytes(&[0xf7, 0xd8, 0xf7, 0xd1]) |m| {
+    m.negl(R(eax));
+    m.notl(R(ecx));
+  }
+  do expect_bytes(&[0x66, 0xf7, 0xd8, 0xf6, 0xd1, 0x66, 0xff, 0x07,
+                    0xfe, 0xca]) |m| {
+    m.negw(R(eax));
+    m.notb(R(ecx));
+    m.incw(M(edi, 0));
+    m.decb(R(edx));
+  }
+
+  let mut m = Asm::new();
+  match m.try_negb(R(edi)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"negb(R): no byte form of the register");
+    },
+    Ok(()) => fail!()
+  }
+  assert_eq!(m.buffer.len(), 0);
+}
+
+#[test]
+fn alu_encoding() {
+  do expect_bytes(&[0x29, 0x47, 0x08, 0x09, 0x17]) |m| {
+    m.subl(M(edi, 8), R(eax));
+    m.orl(M(edi, 0), R(edx));
+  }
+  do expect_bytes(&[0x66, 0x05, 0x34, 0x12, 0x66, 0x83, 0xd9, 0x01]) |m| {
+    m.addw(R(eax), Word(0x1234));
+    m.sbbw(R(ecx), Byte(1));
+  }
+  do expect_bytes(&[0x32, 0xca, 0x80, 0x3f, 0x07]) |m| {
+    m.xorb(R(ecx), R(edx));
+    m.cmpb(M(edi, 0), Byte(7));
+  }
+
+  let mut m = Asm::new();
+  match m.try_addb(R(esi), R(eax)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"addb(R, R): no byte form of the register");
+    },
+    Ok(()) => fail!()
+  }
+  assert_eq!(m.buffer.len(), 0);
+}
+
+#[test]
+fn bit_manipulation_encoding() {
+  do expect_bytes(&[0x0f, 0xa3, 0xc8, 0x0f, 0xba, 0x6f, 0x08, 0x03]) |m| {
+    m.btl(R(eax), R(ecx));
+    m.btsl(M(edi, 8), Byte(3));
+  }
+  do expect_bytes(&[0x0f, 0xbc, 0xc1, 0xf3, 0x0f, 0xb8, 0xc1]) |m| {
+    m.bsfl(R(eax), R(ecx));
+    m.popcntl(R(eax), R(ecx));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0xbd, 0x17, 0xf3, 0x0f, 0xbc, 0xc1]) |m| {
+    m.lzcntl(R(edx), M(edi, 0));
+    m.tzcntl(R(eax), R(ecx));
+  }
+}
+
+#[test]
+fn atomics_encoding() {
+  do expect_bytes(&[0xf0, 0x0f, 0xc1, 0x0f, 0xf0, 0x0f, 0xb1, 0x57, 0x04]) |m| {
+    m.lock();
+    m.xaddl(M(edi, 0), R(ecx));
+    m.lock();
+    m.cmpxchgl(M(edi, 4), R(edx));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xc1, 0x0f, 0x0f, 0xc0, 0x0f,
+                    0x66, 0x0f, 0xb1, 0x57, 0x04, 0x0f, 0xb0, 0x17]) |m| {
+    m.xaddw(M(edi, 0), R(ecx));
+    m.xaddb(M(edi, 0), R(ecx));
+    m.cmpxchgw(M(edi, 4), R(edx));
+    m.cmpxchgb(M(edi, 0), R(edx));
+  }
+  do expect_bytes(&[0x0f, 0xc7, 0x0e, 0x0f, 0xae, 0xf0, 0xf3, 0x90]) |m| {
+    m.cmpxchg8b(M(esi, 0));
+    m.mfence();
+    m.pause();
+  }
+
+  let mut m = Asm::new();
+  match m.try_xaddb(M(edi, 0), R(esi)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"xaddb(M, R): no byte form of the register");
+    },
+    Ok(()) => fail!()
+  }
+}
+
+#[test]
+fn string_instructions_encoding() {
+  do expect_bytes(&[0xf3, 0xa5, 0xf3, 0xaa, 0xf2, 0xae, 0x66, 0xad]) |m| {
+    m.rep();
+    m.movsl();
+    m.rep();
+    m.stosb();
+    m.repne();
+    m.scasb();
+    m.lodsw();
+  }
+}
+
+#[test]
+fn single_precision_encoding() {
+  do expect_bytes(&[0xf3, 0x0f, 0x10, 0x07, 0xf3, 0x0f, 0x11, 0x4e, 0x04]) |m| {
+    m.movss(D(xmm0), M(edi, 0));
+    m.movss(M(esi, 4), D(xmm1));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x58, 0xca, 0xf3, 0x0f, 0x5c, 0xca,
+                    0xf3, 0x0f, 0x59, 0xca, 0xf3, 0x0f, 0x5e, 0xca]) |m| {
+    m.addss(D(xmm1), D(xmm2));
+    m.subss(D(xmm1), D(xmm2));
+    m.mulss(D(xmm1), D(xmm2));
+    m.divss(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x51, 0xca, 0xf3, 0x0f, 0x2a, 0xc0]) |m| {
+    m.sqrtss(D(xmm1), D(xmm2));
+    m.cvtsi2ss(D(xmm0), R(eax));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x2d, 0xc1, 0xf3, 0x0f, 0x2c, 0xc8]) |m| {
+    m.cvtss2si(R(eax), D(xmm1));
+    m.cvttss2si(R(ecx), D(xmm0));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x5a, 0xc1, 0xf2, 0x0f, 0x5a, 0xc1,
+                    0x0f, 0x2e, 0xc1]) |m| {
+    m.cvtss2sd(D(xmm0), D(xmm1));
+    m.cvtsd2ss(D(xmm0), D(xmm1));
+    m.ucomiss(D(xmm0), D(xmm1));
+  }
+}
+
+#[test]
+fn fp_encoding() {
+  do expect_bytes(&[0xf2, 0x0f, 0x10, 0x4f, 0x08, 0xf2, 0x0f, 0x11, 0x0e]) |m| {
+    m.movsd(D(xmm1), M(edi, 8));
+    m.movsd(M(esi, 0), D(xmm1));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x7e, 0xc1, 0x66, 0x0f, 0xd6, 0x17]) |m| {
+    m.movqd(D(xmm0), D(xmm1));
+    m.movqd(M(edi, 0), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0x58, 0xca, 0xf2, 0x0f, 0x5c, 0xca,
+                    0xf2, 0x0f, 0x59, 0xca, 0xf2, 0x0f, 0x5e, 0xca]) |m| {
+    m.addsd(D(xmm1), D(xmm2));
+    m.subsd(D(xmm1), D(xmm2));
+    m.mulsd(D(xmm1), D(xmm2));
+    m.divsd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0x51, 0xca, 0xf2, 0x0f, 0x5d, 0xca,
+                    0xf2, 0x0f, 0x5f, 0xca]) |m| {
+    m.sqrtsd(D(xmm1), D(xmm2));
+    m.minsd(D(xmm1), D(xmm2));
+    m.maxsd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0xc2, 0xca, 0x04,
+                    0xf2, 0x0f, 0xc2, 0x47, 0x08, 0x07]) |m| {
+    m.cmpsd(D(xmm1), D(xmm2), CmpNeq);
+    m.cmpsd(D(xmm0), M(edi, 8), CmpOrd);
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x54, 0xca, 0x66, 0x0f, 0x56, 0xca,
+                    0x66, 0x0f, 0x57, 0xca]) |m| {
+    m.andpd(D(xmm1), D(xmm2));
+    m.orpd(D(xmm1), D(xmm2));
+    m.xorpd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0x2a, 0xc0, 0xf2, 0x0f, 0x2d, 0xc1,
+                    0xf2, 0x0f, 0x2c, 0xc8]) |m| {
+    m.cvtsi2sd(D(xmm0), R(eax));
+    m.cvtsd2si(R(eax), D(xmm1));
+    m.cvttsd2si(R(ecx), D(xmm0));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x0b, 0xc1, 0x0a,
+                    0x66, 0x0f, 0x2e, 0xc1]) |m| {
+    m.roundsd(D(xmm0), D(xmm1), RoundUp);
+    m.ucomisd(D(xmm0), D(xmm1));
+  }
+}
+
+#[test]
+fn packed_encoding() {
+  do expect_bytes(&[0x0f, 0x28, 0x07, 0x0f, 0x29, 0x47, 0x10,
+                    0x66, 0x0f, 0x10, 0xca, 0x66, 0x0f, 0x11, 0x0f]) |m| {
+    m.movaps(D(xmm0), M(edi, 0));
+    m.movaps(M(edi, 16), D(xmm0));
+    m.movupd(D(xmm1), D(xmm2));
+    m.movupd(M(edi, 0), D(xmm1));
+  }
+  do expect_bytes(&[0x0f, 0x58, 0xca, 0x66, 0x0f, 0x5c, 0xca,
+                    0x0f, 0x59, 0xca, 0x66, 0x0f, 0x5e, 0xca]) |m| {
+    m.addps(D(xmm1), D(xmm2));
+    m.subpd(D(xmm1), D(xmm2));
+    m.mulps(D(xmm1), D(xmm2));
+    m.divpd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x0f, 0x5d, 0xca, 0x66, 0x0f, 0x5f, 0xca,
+                    0x0f, 0x51, 0xca]) |m| {
+    m.minps(D(xmm1), D(xmm2));
+    m.maxpd(D(xmm1), D(xmm2));
+    m.sqrtps(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x0f, 0xc6, 0xc1, 0x1b, 0x66, 0x0f, 0xc6, 0xc1, 0x01,
+                    0x66, 0x0f, 0x14, 0xc1, 0x66, 0x0f, 0x15, 0xc1]) |m| {
+    m.shufps(D(xmm0), D(xmm1), Byte(0x1b));
+    m.shufpd(D(xmm0), D(xmm1), Byte(1));
+    m.unpcklpd(D(xmm0), D(xmm1));
+    m.unpckhpd(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x0f, 0x12, 0xc1, 0x0f, 0x16, 0xc1]) |m| {
+    m.movhlps(D(xmm0), D(xmm1));
+    m.movlhps(D(xmm0), D(xmm1));
+  }
+}
+
+#[test]
+fn simd_encoding() {
+  do expect_bytes(&[0x66, 0x0f, 0x6f, 0x07, 0xf3, 0x0f, 0x7f, 0x4f, 0x10]) |m| {
+    m.movdqa(D(xmm0), M(edi, 0));
+    m.movdqu(M(edi, 16), D(xmm1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xfd, 0xca, 0x66, 0x0f, 0xf9, 0xca,
+                    0x66, 0x0f, 0xd5, 0xca, 0x66, 0x0f, 0x38, 0x40, 0xca]) |m| {
+    m.paddw(D(xmm1), D(xmm2));
+    m.psubw(D(xmm1), D(xmm2));
+    m.pmullw(D(xmm1), D(xmm2));
+    m.pmulld(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x75, 0xc1, 0x66, 0x0f, 0x38, 0x37, 0xc1,
+                    0x66, 0x0f, 0xef, 0xc1]) |m| {
+    m.pcmpeqw(D(xmm0), D(xmm1));
+    m.pcmpgtq(D(xmm0), D(xmm1));
+    m.pxor(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xe1, 0xca, 0x66, 0x0f, 0x71, 0xe1, 0x03,
+                    0x66, 0x0f, 0x73, 0xf1, 0x08]) |m| {
+    m.psraw(D(xmm1), D(xmm2));
+    m.psraw(D(xmm1), Byte(3));
+    m.psllq(D(xmm1), Byte(8));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x70, 0xc1, 0x1b, 0x66, 0x0f, 0x38, 0x00, 0xc1,
+                    0x66, 0x0f, 0x3a, 0x22, 0xc1, 0x01]) |m| {
+    m.pshufd(D(xmm0), D(xmm1), Byte(0x1b));
+    m.pshufb(D(xmm0), D(xmm1));
+    m.pinsrd(D(xmm0), R(ecx), Byte(1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x16, 0xc8, 0x02,
+                    0x66, 0x0f, 0xd7, 0xc1]) |m| {
+    m.pextrd(R(eax), D(xmm1), Byte(2));
+    m.pmovmskb(R(eax), D(xmm1));
+  }
+}
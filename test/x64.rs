@@ -183,3 +183,1283 @@ fn fp() {
     m.ret(Empty);
   }
 }
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn sib() {
+  do run_test(13589, 27178) |m| {
+    m.pushq(R(rbp));
+    m.movq(R(rbp), R(rsp));
+
+    // Reserve an array of four quads on stack
+    m.subq(R(rsp), Byte(32));
+    m.movq(R(rdx), R(rsp));
+
+    // a[2] = arg
+    m.movq(R(rcx), Long(2));
+    m.movq(MI(rdx, rcx, Scale8, 0), R(rsi));
+
+    // a[3] = arg
+    m.movq(R(r9), Long(1));
+    m.movq(MI(rdx, r9, Scale8, 16), R(rsi));
+
+    m.movq(R(rax), M(rdx, 16));
+    m.addq(R(rax), M(rdx, 24));
+
+    m.movq(R(rsp), R(rbp));
+    m.popq(R(rbp));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn sib_encoding() {
+  do expect_bytes(&[0x48, 0x8b, 0x44, 0xcb, 0x10]) |m| {
+    m.movq(R(rax), MI(rbx, rcx, Scale8, 0x10));
+  }
+  do expect_bytes(&[0x4a, 0x8b, 0x04, 0x8b]) |m| {
+    m.movq(R(rax), MI(rbx, r9, Scale4, 0));
+  }
+  do expect_bytes(&[0x4d, 0x89, 0xbc, 0x80, 0x00, 0x01, 0x00, 0x00]) |m| {
+    m.movq(MI(r8, rax, Scale4, 0x100), R(r15));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn stack_spill() {
+  do run_test(13589, 13589) |m| {
+    m.subq(R(rsp), Byte(16));
+
+    m.movq(M(rsp, 8), R(rsi));
+    m.movq(R(r12), R(rsp));
+    m.movq(R(rax), M(r12, 8));
+
+    m.addq(R(rsp), Byte(16));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn special_bases() {
+  do expect_bytes(&[0x48, 0x8b, 0x04, 0x24]) |m| {
+    m.movq(R(rax), M(rsp, 0));
+  }
+  do expect_bytes(&[0x48, 0x89, 0x44, 0x24, 0x08]) |m| {
+    m.movq(M(rsp, 8), R(rax));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x84, 0x24, 0x00, 0x01, 0x00, 0x00]) |m| {
+    m.movq(R(rax), M(rsp, 0x100));
+  }
+  do expect_bytes(&[0x49, 0x89, 0x04, 0x24]) |m| {
+    m.movq(M(r12, 0), R(rax));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x45, 0x00]) |m| {
+    m.movq(R(rax), M(rbp, 0));
+  }
+  do expect_bytes(&[0x49, 0x8b, 0x45, 0x00]) |m| {
+    m.movq(R(rax), M(r13, 0));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x44, 0x0d, 0x00]) |m| {
+    m.movq(R(rax), MI(rbp, rcx, Scale1, 0));
+  }
+  do expect_bytes(&[0x41, 0xff, 0x34, 0x24]) |m| {
+    m.pushq(M(r12, 0));
+  }
+}
+
+#[test]
+fn signed_displacement() {
+  do expect_bytes(&[0x48, 0x8b, 0x45, 0xf8]) |m| {
+    m.movq(R(rax), M(rbp, -8));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x45, 0x80]) |m| {
+    m.movq(R(rax), M(rbp, -128));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x45, 0x7f]) |m| {
+    m.movq(R(rax), M(rbp, 127));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x85, 0x80, 0x00, 0x00, 0x00]) |m| {
+    m.movq(R(rax), M(rbp, 128));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x85, 0x7f, 0xff, 0xff, 0xff]) |m| {
+    m.movq(R(rax), M(rbp, -129));
+  }
+  do expect_bytes(&[0x48, 0x8b, 0x44, 0xcc, 0xf0]) |m| {
+    m.movq(R(rax), MI(rsp, rcx, Scale8, -16));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn rip_relative() {
+  do run_test(0, 0x1236) |m| {
+    let data = @mut Label::new();
+
+    m.addq(rip(data), Byte(1));
+    m.addq(rip(data), Long(1));
+    m.movq(R(rax), rip(data));
+    m.ret(Empty);
+
+    m.bind(&mut *data);
+    m.emitq(0x1234);
+  }
+}
+
+#[test]
+fn rip_relative_encoding() {
+  do expect_bytes(&[0x48, 0x8b, 0x05, 0x00, 0x00, 0x00, 0x00]) |m| {
+    let data = @mut Label::new();
+    m.movq(R(rax), rip(data));
+  }
+  do expect_bytes(&[0x4c, 0x39, 0x3d, 0x00, 0x00, 0x00, 0x00]) |m| {
+    let data = @mut Label::new();
+    m.cmpq(rip(data), R(r15));
+  }
+}
+
+#[test]
+fn invalid_operands() {
+  let mut m = Asm::new();
+
+  match m.try_movq(Byte(1), R(rax)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"movq(Byte, R): unsupported operands");
+    },
+    Ok(()) => fail!()
+  }
+  match m.try_divq(Long(1)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"divq(Long): expected register or memory");
+    },
+    Ok(()) => fail!()
+  }
+
+  // Nothing should be emitted for invalid instructions
+  assert_eq!(m.buffer.len(), 0);
+
+  assert!(m.try_addq(R(rax), Byte(1)).is_ok());
+  assert_eq!(m.buffer.len(), 4);
+}
+
+#[test]
+fn invalid_index() {
+  let mut m = Asm::new();
+
+  match m.try_movq(R(rax), MI(rbx, rsp, Scale1, 0)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"movq(R, MI): unsupported operands");
+    },
+    Ok(()) => fail!()
+  }
+  assert!(m.try_pushq(MI(rbx, rsp, Scale4, 8)).is_err());
+  assert!(m.try_addq(MI(rbx, rsp, Scale1, 0), Byte(1)).is_err());
+  assert!(m.try_movsd(D(xmm0), MI(rbx, rsp, Scale8, 0)).is_err());
+
+  // Operands of the wrong kind are errors rather than failures
+  assert!(m.try_addq(R(rax), D(xmm0)).is_err());
+  assert!(m.try_movq(M(rax, 0), _Operation(0)).is_err());
+  assert!(m.try_shlq(R(rax), Word(1)).is_err());
+  assert_eq!(m.buffer.len(), 0);
+
+  // r12 is fine as an index
+  assert!(m.try_movq(R(rax), MI(rbx, r12, Scale1, 0)).is_ok());
+}
+
+#[test]
+#[should_fail]
+fn invalid_operands_fail() {
+  let mut m = Asm::new();
+  m.movq(Byte(1), R(rax));
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn short_jumps() {
+  do run_test(100, 400) |m| {
+    m.movq(R(rax), Long(0));
+    m.movq(R(rcx), R(rsi));
+
+    let mut loop_start = Label::relaxed();
+    let mut done = Label::relaxed();
+    let mut far = Label::relaxed();
+
+    m.bind(&mut loop_start);
+    m.cmpq(R(rcx), Long(0));
+    m.jccl(IfEqual, &mut done);
+
+    m.addq(R(rax), Byte(4));
+    m.decq(R(rcx));
+    m.jmpb(&mut loop_start);
+
+    // Should be grown to rel32
+    m.bind(&mut done);
+    m.jmpl(&mut far);
+    for 200.times {
+      m.int3();
+    }
+    m.bind(&mut far);
+    m.ret(Empty);
+    m.finalize();
+  }
+}
+
+#[test]
+fn short_jumps_encoding() {
+  do expect_bytes(&[0x90,
+                     0xeb, 0x00,
+                     0x74, 0x00,
+                     0xe9, 0x00, 0x00, 0x00, 0x00]) |m| {
+    let mut relaxed = Label::relaxed();
+    let mut l = Label::new();
+
+    m.bind(&mut relaxed);
+    m.nop();
+    m.jmpl(&mut relaxed);
+    m.jccb(IfZero, &mut l);
+    m.jmpl(&mut l);
+  }
+
+  let mut m = Asm::new();
+  let mut forward = Label::relaxed();
+  let mut near = Label::relaxed();
+  m.jccl(IfLess, &mut forward);
+  m.jmpl(&mut near);
+  m.bind(&mut near);
+  for 126.times {
+    m.nop();
+  }
+  m.bind(&mut forward);
+  assert_eq!(m.buffer.len(), 130);
+
+  // Only the first jump is out of range
+  let growth = m.finalize();
+  assert_eq!(m.buffer.len(), 134);
+  assert_eq!(m.buffer[0], 0x0f);
+  assert_eq!(m.buffer[1], 0x8c);
+  assert_eq!(m.buffer[6], 0xeb);
+
+  // Bound offsets are translated through the returned growth
+  let AsmOffset(near_at) = relaxed_offset(growth, near.offset.unwrap());
+  let AsmOffset(forward_at) = relaxed_offset(growth, forward.offset.unwrap());
+  assert_eq!(near_at, 8);
+  assert_eq!(forward_at, 134);
+
+  // Jumps out of range in the same pass grow together
+  let mut m = Asm::new();
+  let mut end = Label::relaxed();
+  m.jmpl(&mut end);
+  m.jccl(IfZero, &mut end);
+  for 200.times {
+    m.nop();
+  }
+  m.bind(&mut end);
+  m.finalize();
+  assert_eq!(m.buffer.len(), 211);
+  assert_eq!(m.buffer[0], 0xe9);
+  assert_eq!(m.buffer[5], 0x0f);
+  assert_eq!(m.buffer[6], 0x84);
+
+  // Byte uses other than jumps are never grown
+  let mut m = Asm::new();
+  let mut target = Label::relaxed();
+  m.emitb(0xe3);
+  m.emit_use(&mut target, RelocRelative, RelocByte, -1);
+  m.bind(&mut target);
+  assert!(!m.infos[0].relax);
+}
+
+#[test]
+fn jump_conditions() {
+  do expect_bytes(&[0x0f, 0x82, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x86, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x87, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x83, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x88, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x89, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x8a, 0x00, 0x00, 0x00, 0x00,
+                    0x0f, 0x8b, 0x00, 0x00, 0x00, 0x00]) |m| {
+    let mut l = Label::new();
+    m.jccl(IfBelow, &mut l);
+    m.jccl(IfBelowOrEqual, &mut l);
+    m.jccl(IfAbove, &mut l);
+    m.jccl(IfAboveOrEqual, &mut l);
+    m.jccl(IfSign, &mut l);
+    m.jccl(IfNotSign, &mut l);
+    m.jccl(IfParity, &mut l);
+    m.jccl(IfNotParity, &mut l);
+  }
+  do expect_bytes(&[0x72, 0x00, 0x73, 0x00]) |m| {
+    let mut l = Label::new();
+    m.jccb(IfCarry, &mut l);
+    m.jccb(IfNotCarry, &mut l);
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn unsigned_branching() {
+  // -1 is above 1 when compared as unsigned
+  do run_test(-1, 1) |m| {
+    let mut done = Label::new();
+
+    m.movq(R(rax), Long(0));
+    m.cmpq(R(rsi), Byte(1));
+    m.jccl(IfBelowOrEqual, &mut done);
+    m.movq(R(rax), Long(1));
+    m.bind(&mut done);
+    m.ret(Empty);
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn conditional_move() {
+  do run_test(13589, 13589) |m| {
+    // max(arg, 1234)
+    m.movq(R(rax), Long(1234));
+    m.cmpq(R(rax), R(rsi));
+    m.cmovq(IfLess, R(rax), R(rsi));
+    m.ret(Empty);
+  }
+  do run_test(7, 1) |m| {
+    m.movq(R(rax), Long(0));
+    m.cmpq(R(rsi), Byte(7));
+    m.setcc(IfEqual, R(rax));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn conditional_move_encoding() {
+  do expect_bytes(&[0x48, 0x0f, 0x4c, 0xc1]) |m| {
+    m.cmovq(IfLess, R(rax), R(rcx));
+  }
+  do expect_bytes(&[0x4c, 0x0f, 0x44, 0x43, 0x08]) |m| {
+    m.cmovq(IfEqual, R(r8), M(rbx, 8));
+  }
+  do expect_bytes(&[0x0f, 0x47, 0xc2]) |m| {
+    m.cmovl(IfAbove, R(rax), R(rdx));
+  }
+  do expect_bytes(&[0x0f, 0x94, 0xc0]) |m| {
+    m.setcc(IfEqual, R(rax));
+  }
+  do expect_bytes(&[0x40, 0x0f, 0x95, 0xc6]) |m| {
+    m.setcc(IfNotEqual, R(rsi));
+  }
+  do expect_bytes(&[0x41, 0x0f, 0x92, 0xc1]) |m| {
+    m.setcc(IfBelow, R(r9));
+  }
+  do expect_bytes(&[0x0f, 0x9f, 0x07]) |m| {
+    m.setcc(IfGreater, M(rdi, 0));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn lea() {
+  do run_test(1000, 3016) |m| {
+    // rax = rsi + rsi * 2 + 16
+    m.leaq(R(rax), MI(rsi, rsi, Scale2, 16));
+    m.ret(Empty);
+  }
+  do run_test(0, 0x1234) |m| {
+    let data = @mut Label::new();
+
+    m.leaq(R(rcx), rip(data));
+    m.movq(R(rax), M(rcx, 0));
+    m.ret(Empty);
+
+    m.bind(&mut *data);
+    m.emitq(0x1234);
+  }
+}
+
+#[test]
+fn lea_encoding() {
+  do expect_bytes(&[0x48, 0x8d, 0x44, 0x76, 0x10]) |m| {
+    m.leaq(R(rax), MI(rsi, rsi, Scale2, 16));
+  }
+  do expect_bytes(&[0x4d, 0x8d, 0x7c, 0x24, 0xf8]) |m| {
+    m.leaq(R(r15), M(r12, -8));
+  }
+  do expect_bytes(&[0x48, 0x8d, 0x0d, 0x00, 0x00, 0x00, 0x00]) |m| {
+    let data = @mut Label::new();
+    m.leaq(R(rcx), rip(data));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_leaq(R(rax), R(rcx)).is_err());
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn operand_sizes() {
+  do run_test(0x1234, 0x1237) |m| {
+    // 32-bit moves zero the upper half
+    m.movq(R(rax), Long(-1));
+    m.movl(R(rax), R(rsi));
+
+    // Byte and word stores into stack slot
+    m.subq(R(rsp), Byte(8));
+    m.movq(M(rsp, 0), Long(0));
+    m.movw(M(rsp, 0), R(rax));
+    m.addb(M(rsp, 0), Byte(3));
+    m.movq(R(rax), M(rsp, 0));
+
+    m.addq(R(rsp), Byte(8));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn operand_sizes_encoding() {
+  do expect_bytes(&[0x8b, 0xc1]) |m| {
+    m.movl(R(rax), R(rcx));
+  }
+  do expect_bytes(&[0xb8, 0x01, 0x00, 0x00, 0x00]) |m| {
+    m.movl(R(rax), Long(1));
+  }
+  do expect_bytes(&[0xc7, 0x07, 0x01, 0x00, 0x00, 0x00]) |m| {
+    m.movl(M(rdi, 0), Long(1));
+  }
+  do expect_bytes(&[0x66, 0x89, 0x47, 0x02]) |m| {
+    m.movw(M(rdi, 2), R(rax));
+  }
+  do expect_bytes(&[0x66, 0xb9, 0x34, 0x12]) |m| {
+    m.movw(R(rcx), Word(0x1234));
+  }
+  do expect_bytes(&[0x40, 0x88, 0x37]) |m| {
+    m.movb(M(rdi, 0), R(rsi));
+  }
+  do expect_bytes(&[0x41, 0xb0, 0x01]) |m| {
+    m.movb(R(r8), Byte(1));
+  }
+  do expect_bytes(&[0x8a, 0x07]) |m| {
+    m.movb(R(rax), M(rdi, 0));
+  }
+  do expect_bytes(&[0x05, 0x01, 0x00, 0x00, 0x00]) |m| {
+    m.addl(R(rax), Long(1));
+  }
+  do expect_bytes(&[0x66, 0x83, 0xc1, 0x01]) |m| {
+    m.addw(R(rcx), Byte(1));
+  }
+  do expect_bytes(&[0x04, 0x01]) |m| {
+    m.addb(R(rax), Byte(1));
+  }
+  do expect_bytes(&[0x80, 0x07, 0x01]) |m| {
+    m.addb(M(rdi, 0), Byte(1));
+  }
+  do expect_bytes(&[0x29, 0x0f]) |m| {
+    m.subl(M(rdi, 0), R(rcx));
+  }
+  do expect_bytes(&[0x41, 0x81, 0xe1, 0xff, 0x00, 0x00, 0x00]) |m| {
+    m.andl(R(r9), Long(0xff));
+  }
+  do expect_bytes(&[0x40, 0x0a, 0xfe]) |m| {
+    m.orb(R(rdi), R(rsi));
+  }
+  do expect_bytes(&[0x66, 0x35, 0x01, 0x00]) |m| {
+    m.xorw(R(rax), Word(1));
+  }
+  do expect_bytes(&[0xff, 0xc0, 0x40, 0xfe, 0xc6, 0x66, 0xff, 0x0f]) |m| {
+    m.incl(R(rax));
+    m.incb(R(rsi));
+    m.decw(M(rdi, 0));
+  }
+  do expect_bytes(&[0x3c, 0x01, 0x83, 0xf9, 0x01]) |m| {
+    m.cmpb(R(rax), Byte(1));
+    m.cmpl(R(rcx), Byte(1));
+  }
+  do expect_bytes(&[0xa8, 0x01, 0x66, 0x85, 0xc8]) |m| {
+    m.testb(R(rax), Byte(1));
+    m.testw(R(rax), R(rcx));
+  }
+  do expect_bytes(&[0xf7, 0x07, 0x01, 0x00, 0x00, 0x00]) |m| {
+    m.testl(M(rdi, 0), Long(1));
+  }
+  do expect_bytes(&[0xd1, 0xe0, 0x66, 0xc1, 0xe1, 0x03, 0xd0, 0xe8,
+                    0x40, 0xd2, 0xfe]) |m| {
+    m.shll(R(rax), Byte(1));
+    m.shlw(R(rcx), Byte(3));
+    m.shrb(R(rax), Byte(1));
+    m.sarb(R(rsi), R(rcx));
+  }
+  do expect_bytes(&[0xf7, 0xe1, 0x66, 0xf7, 0x37, 0x40, 0xf6, 0xff,
+                    0x41, 0xf6, 0xe8]) |m| {
+    m.mull(R(rcx));
+    m.divw(M(rdi, 0));
+    m.idivb(R(rdi));
+    m.imulb(R(r8));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_movw(R(rax), Long(1)).is_err());
+  assert!(m.try_addb(R(rax), Long(1)).is_err());
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn sign_extension() {
+  do run_test(0xfe, -2) |m| {
+    m.movqsxb(R(rax), R(rsi));
+    m.ret(Empty);
+  }
+  do run_test(0xfffe, -2) |m| {
+    m.subq(R(rsp), Byte(8));
+    m.movq(M(rsp, 0), R(rsi));
+    m.movqsxw(R(rax), M(rsp, 0));
+    m.addq(R(rsp), Byte(8));
+    m.ret(Empty);
+  }
+  do run_test(0xffff_fffe, -2) |m| {
+    m.movqsxd(R(rax), R(rsi));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn sign_extension_encoding() {
+  do expect_bytes(&[0x48, 0x0f, 0xbe, 0xc6]) |m| {
+    m.movqsxb(R(rax), R(rsi));
+  }
+  do expect_bytes(&[0x4c, 0x0f, 0xbf, 0x47, 0x02]) |m| {
+    m.movqsxw(R(r8), M(rdi, 2));
+  }
+  do expect_bytes(&[0x49, 0x63, 0xc1]) |m| {
+    m.movqsxd(R(rax), R(r9));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn shifts() {
+  do run_test(3, 32) |m| {
+    m.movq(R(rcx), R(rsi));
+    m.movq(R(rax), Long(1));
+    m.shlq(R(rax), R(rcx));
+    m.shlq(R(rax), Byte(1));
+    m.rorq(R(rax), Byte(1));
+    m.rolq(R(rax), R(rcx));
+    m.sarq(R(rax), Byte(1));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn shifts_encoding() {
+  do expect_bytes(&[0x48, 0xd1, 0xe0]) |m| {
+    m.shlq(R(rax), Byte(1));
+  }
+  do expect_bytes(&[0x48, 0xc1, 0xe8, 0x03]) |m| {
+    m.shrq(R(rax), Byte(3));
+  }
+  do expect_bytes(&[0x49, 0xd3, 0xf8]) |m| {
+    m.sarq(R(r8), R(rcx));
+  }
+  do expect_bytes(&[0x48, 0xd1, 0xc0, 0x48, 0xc1, 0x4f, 0x08, 0x02]) |m| {
+    m.rolq(R(rax), Byte(1));
+    m.rorq(M(rdi, 8), Byte(2));
+  }
+  do expect_bytes(&[0x48, 0xd3, 0xd2, 0x48, 0xd1, 0xdb]) |m| {
+    m.rclq(R(rdx), R(rcx));
+    m.rcrq(R(rbx), Byte(1));
+  }
+  do expect_bytes(&[0x66, 0xd3, 0xc0, 0x40, 0xd0, 0xdc,
+                    0x41, 0xc1, 0xc9, 0x02]) |m| {
+    m.rolw(R(rax), R(rcx));
+    m.rcrb(R(rsp), Byte(1));
+    m.rorl(R(r9), Byte(2));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_shlq(R(rax), R(rdx)).is_err());
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn imul() {
+  do run_test(5, 75) |m| {
+    m.imulq3(R(rax), R(rsi), Byte(3));
+    m.imulq2(R(rax), R(rsi));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn imul_encoding() {
+  do expect_bytes(&[0x48, 0x0f, 0xaf, 0xc1]) |m| {
+    m.imulq2(R(rax), R(rcx));
+  }
+  do expect_bytes(&[0x4c, 0x0f, 0xaf, 0x4f, 0x08]) |m| {
+    m.imulq2(R(r9), M(rdi, 8));
+  }
+  do expect_bytes(&[0x48, 0x6b, 0xc1, 0x03]) |m| {
+    m.imulq3(R(rax), R(rcx), Byte(3));
+  }
+  do expect_bytes(&[0x49, 0x69, 0xd0, 0xe8, 0x03, 0x00, 0x00]) |m| {
+    m.imulq3(R(rdx), R(r8), Long(1000));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn signed_division() {
+  do run_test(-7, -3) |m| {
+    m.movq(R(rax), R(rsi));
+    m.cqo();
+    m.movq(R(rcx), Long(2));
+    m.idivq(R(rcx));
+    m.ret(Empty);
+  }
+  do run_test(0xff, -1) |m| {
+    m.movq(R(rax), R(rsi));
+    m.cbw();
+    m.cwde();
+    m.cdqe();
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn sign_extension_helpers_encoding() {
+  do expect_bytes(&[0x48, 0x99, 0x99, 0x66, 0x99]) |m| {
+    m.cqo();
+    m.cdq();
+    m.cwd();
+  }
+  do expect_bytes(&[0x48, 0x98, 0x98, 0x66, 0x98]) |m| {
+    m.cdqe();
+    m.cwde();
+    m.cbw();
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn carry_arithmetic() {
+  do run_test(-1, 1) |m| {
+    m.movq(R(rax), R(rsi));
+    m.xorq(R(rdx), R(rdx));
+    m.addq(R(rax), R(rsi));
+    m.adcq(R(rdx), Byte(0));
+    m.movq(R(rax), R(rdx));
+    m.ret(Empty);
+  }
+  do run_test(5, -1) |m| {
+    m.xorq(R(rax), R(rax));
+    m.subq(R(rax), R(rsi));
+    m.sbbq(R(rax), R(rax));
+    m.ret(Empty);
+  }
+  do run_test(5, 4) |m| {
+    m.movq(R(rax), R(rsi));
+    m.negq(R(rax));
+    m.notq(R(rax));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn carry_arithmetic_encoding() {
+  do expect_bytes(&[0x48, 0x15, 0x00, 0x01, 0x00, 0x00]) |m| {
+    m.adcq(R(rax), Long(0x100));
+  }
+  do expect_bytes(&[0x48, 0x83, 0xd1, 0x01, 0x48, 0x11, 0x57, 0x08]) |m| {
+    m.adcq(R(rcx), Byte(1));
+    m.adcq(M(rdi, 8), R(rdx));
+  }
+  do expect_bytes(&[0x4c, 0x1b, 0xc0, 0x48, 0x19, 0x07]) |m| {
+    m.sbbq(R(r8), R(rax));
+    m.sbbq(M(rdi, 0), R(rax));
+  }
+  do expect_bytes(&[0x48, 0xf7, 0xd8, 0x49, 0xf7, 0x17]) |m| {
+    m.negq(R(rax));
+    m.notq(M(r15, 0));
+  }
+  do expect_bytes(&[0xf7, 0xd8, 0x66, 0xf7, 0x17, 0x40, 0xf6, 0xde,
+                    0x41, 0xf6, 0xd2]) |m| {
+    m.negl(R(rax));
+    m.notw(M(rdi, 0));
+    m.negb(R(rsi));
+    m.notb(R(r10));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn alu_memory_destination() {
+  do run_test(5, 95) |m| {
+    m.subq(R(rsp), Byte(16));
+
+    m.movq(M(rsp, 8), Long(100));
+    m.subq(M(rsp, 8), R(rsi));
+    m.movq(R(rax), M(rsp, 8));
+
+    m.addq(R(rsp), Byte(16));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn alu_encoding() {
+  do expect_bytes(&[0x48, 0x29, 0x47, 0x08, 0x48, 0x0b, 0xca]) |m| {
+    m.subq(M(rdi, 8), R(rax));
+    m.orq(R(rcx), R(rdx));
+  }
+  do expect_bytes(&[0x48, 0x09, 0x17, 0x48, 0x83, 0xf8, 0x01]) |m| {
+    m.orq(M(rdi, 0), R(rdx));
+    m.cmpq(R(rax), Byte(1));
+  }
+  do expect_bytes(&[0x13, 0xc1, 0x40, 0x80, 0xde, 0x01]) |m| {
+    m.adcl(R(rax), R(rcx));
+    m.sbbb(R(rsi), Byte(1));
+  }
+  do expect_bytes(&[0x66, 0x81, 0x17, 0x34, 0x12]) |m| {
+    m.adcw(M(rdi, 0), Word(0x1234));
+  }
+
+  let mut m = Asm::new();
+  match m.try_subb(Long(1), R(rax)) {
+    Err(err) => {
+      assert_eq!(err.to_str(), ~"subb(Long, R): unsupported operands");
+    },
+    Ok(()) => fail!()
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn bit_manipulation() {
+  do run_test(0x30, 0x21) |m| {
+    m.movq(R(rax), R(rsi));
+    m.btsq(R(rax), Byte(0));
+    m.btrq(R(rax), Byte(4));
+    m.ret(Empty);
+  }
+  do run_test(0x100, 8) |m| {
+    m.bsrq(R(rax), R(rsi));
+    m.ret(Empty);
+  }
+  do run_test(0xff00ff, 16) |m| {
+    m.popcntq(R(rax), R(rsi));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn bit_manipulation_encoding() {
+  do expect_bytes(&[0x48, 0x0f, 0xa3, 0xc8, 0x4c, 0x0f, 0xbb, 0xc8]) |m| {
+    m.btq(R(rax), R(rcx));
+    m.btcq(R(rax), R(r9));
+  }
+  do expect_bytes(&[0x48, 0x0f, 0xba, 0x6f, 0x08, 0x03]) |m| {
+    m.btsq(M(rdi, 8), Byte(3));
+  }
+  do expect_bytes(&[0x49, 0x0f, 0xba, 0xf0, 0x01]) |m| {
+    m.btrq(R(r8), Byte(1));
+  }
+  do expect_bytes(&[0x48, 0x0f, 0xbc, 0xc1, 0x48, 0x0f, 0xbd, 0x17]) |m| {
+    m.bsfq(R(rax), R(rcx));
+    m.bsrq(R(rdx), M(rdi, 0));
+  }
+  do expect_bytes(&[0xf3, 0x48, 0x0f, 0xb8, 0xc1]) |m| {
+    m.popcntq(R(rax), R(rcx));
+  }
+  do expect_bytes(&[0xf3, 0x4c, 0x0f, 0xbd, 0xc0]) |m| {
+    m.lzcntq(R(r8), R(rax));
+  }
+  do expect_bytes(&[0xf3, 0x48, 0x0f, 0xbc, 0x06]) |m| {
+    m.tzcntq(R(rax), M(rsi, 0));
+  }
+}
+
+#[test]
+fn bmi_encoding() {
+  do expect_bytes(&[0xc4, 0xe2, 0xe0, 0xf2, 0xc1]) |m| {
+    m.andnq(R(rax), R(rbx), R(rcx));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xf0, 0xf7, 0x07]) |m| {
+    m.bextrq(R(rax), M(rdi, 0), R(rcx));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xf8, 0xf3, 0xc9]) |m| {
+    m.blsrq(R(rax), R(rcx));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xe9, 0xf7, 0xc1]) |m| {
+    m.shlxq(R(rax), R(rcx), R(rdx));
+  }
+  do expect_bytes(&[0xc4, 0x42, 0xaa, 0xf7, 0xc1]) |m| {
+    m.sarxq(R(r8), R(r9), R(r10));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xeb, 0xf7, 0xc1]) |m| {
+    m.shrxq(R(rax), R(rcx), R(rdx));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xe3, 0xf5, 0xc1]) |m| {
+    m.pdepq(R(rax), R(rbx), R(rcx));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xe2, 0xf5, 0xc1]) |m| {
+    m.pextq(R(rax), R(rbx), R(rcx));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xe3, 0xf6, 0xc1]) |m| {
+    m.mulxq(R(rax), R(rbx), R(rcx));
+  }
+  do expect_bytes(&[0xc4, 0xe3, 0xfb, 0xf0, 0xc1, 0x03]) |m| {
+    m.rorxq(R(rax), R(rcx), Byte(3));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_shlxq(R(rax), R(rcx), Long(1)).is_err());
+  assert_eq!(m.buffer.len(), 0);
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn atomics() {
+  do run_test(10, 17) |m| {
+    m.subq(R(rsp), Byte(16));
+    m.movq(M(rsp, 8), R(rsi));
+
+    // Swap in 7, then add 3 and fetch the old value
+    m.movq(R(rax), R(rsi));
+    m.movq(R(rcx), Long(7));
+    m.lock();
+    m.cmpxchgq(M(rsp, 8), R(rcx));
+    m.movq(R(rcx), Long(3));
+    m.lock();
+    m.xaddq(M(rsp, 8), R(rcx));
+    m.mfence();
+
+    m.movq(R(rax), M(rsp, 8));
+    m.addq(R(rax), R(rcx));
+    m.addq(R(rsp), Byte(16));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn atomics_encoding() {
+  do expect_bytes(&[0xf0, 0x48, 0x01, 0x07]) |m| {
+    m.lock();
+    m.addq(M(rdi, 0), R(rax));
+  }
+  do expect_bytes(&[0x48, 0x0f, 0xc1, 0x0f, 0x49, 0x0f, 0xb1, 0x50, 0x08]) |m| {
+    m.xaddq(M(rdi, 0), R(rcx));
+    m.cmpxchgq(M(r8, 8), R(rdx));
+  }
+  do expect_bytes(&[0x0f, 0xc1, 0x0f, 0x66, 0x0f, 0xc1, 0x0f,
+                    0x40, 0x0f, 0xc0, 0x37]) |m| {
+    m.xaddl(M(rdi, 0), R(rcx));
+    m.xaddw(M(rdi, 0), R(rcx));
+    m.xaddb(M(rdi, 0), R(rsi));
+  }
+  do expect_bytes(&[0x41, 0x0f, 0xb1, 0x50, 0x08, 0x66, 0x0f, 0xb1, 0x17,
+                    0x0f, 0xb0, 0x0f]) |m| {
+    m.cmpxchgl(M(r8, 8), R(rdx));
+    m.cmpxchgw(M(rdi, 0), R(rdx));
+    m.cmpxchgb(M(rdi, 0), R(rcx));
+  }
+  do expect_bytes(&[0x0f, 0xc7, 0x0f, 0x48, 0x0f, 0xc7, 0x0f]) |m| {
+    m.cmpxchg8b(M(rdi, 0));
+    m.cmpxchg16b(M(rdi, 0));
+  }
+  do expect_bytes(&[0x0f, 0xae, 0xf0, 0x0f, 0xae, 0xe8,
+                    0x0f, 0xae, 0xf8, 0xf3, 0x90]) |m| {
+    m.mfence();
+    m.lfence();
+    m.sfence();
+    m.pause();
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_cmpxchg16b(R(rax)).is_err());
+  assert!(m.try_xaddl(M(rdi, 0), Long(1)).is_err());
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn string_instructions() {
+  do run_test(0x1234, 0x1234) |m| {
+    m.subq(R(rsp), Byte(16));
+    m.movq(M(rsp, 0), R(rsi));
+
+    m.leaq(R(rsi), M(rsp, 0));
+    m.leaq(R(rdi), M(rsp, 8));
+    m.movq(R(rcx), Long(8));
+    m.rep();
+    m.movsb();
+
+    m.movq(R(rax), M(rsp, 8));
+    m.addq(R(rsp), Byte(16));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn string_instructions_encoding() {
+  do expect_bytes(&[0xf3, 0xa4, 0xf3, 0x48, 0xab, 0xf3, 0xa6]) |m| {
+    m.rep();
+    m.movsb();
+    m.rep();
+    m.stosq();
+    m.repe();
+    m.cmpsb();
+  }
+  do expect_bytes(&[0xf2, 0x66, 0xaf, 0xad, 0x66, 0xa5, 0x48, 0xa7]) |m| {
+    m.repne();
+    m.scasw();
+    m.lodsl();
+    m.movsw();
+    m.cmpsq();
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn single_precision() {
+  do run_test(8, 4) |m| {
+    // x = sqrt(arg * 2)
+    m.cvtsi2ss(D(xmm0), R(rsi));
+    m.addss(D(xmm0), D(xmm0));
+    m.sqrtss(D(xmm0), D(xmm0));
+
+    // Round-trip through double
+    m.cvtss2sd(D(xmm1), D(xmm0));
+    m.cvtsd2ss(D(xmm2), D(xmm1));
+    m.cvttss2si(R(rax), D(xmm2));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn single_precision_encoding() {
+  do expect_bytes(&[0xf3, 0x0f, 0x10, 0x07]) |m| {
+    m.movss(D(xmm0), M(rdi, 0));
+  }
+  do expect_bytes(&[0xf3, 0x45, 0x0f, 0x11, 0x48, 0x04]) |m| {
+    m.movss(M(r8, 4), D(xmm9));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x58, 0xca, 0xf3, 0x0f, 0x5c, 0xca]) |m| {
+    m.addss(D(xmm1), D(xmm2));
+    m.subss(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x59, 0xca, 0xf3, 0x0f, 0x5e, 0xca]) |m| {
+    m.mulss(D(xmm1), D(xmm2));
+    m.divss(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x51, 0xca]) |m| {
+    m.sqrtss(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf3, 0x48, 0x0f, 0x2a, 0xc0]) |m| {
+    m.cvtsi2ss(D(xmm0), R(rax));
+  }
+  do expect_bytes(&[0xf3, 0x48, 0x0f, 0x2d, 0xc1]) |m| {
+    m.cvtss2si(R(rax), D(xmm1));
+  }
+  do expect_bytes(&[0xf3, 0x48, 0x0f, 0x2c, 0xc8]) |m| {
+    m.cvttss2si(R(rcx), D(xmm0));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x5a, 0xc1, 0xf2, 0x0f, 0x5a, 0xc1]) |m| {
+    m.cvtss2sd(D(xmm0), D(xmm1));
+    m.cvtsd2ss(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x0f, 0x2e, 0xc1]) |m| {
+    m.ucomiss(D(xmm0), D(xmm1));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn fp_arithmetic() {
+  do run_test(10, 7) |m| {
+    // x = max(min(arg - 3, 100), 3)
+    m.cvtsi2sd(D(xmm0), R(rsi));
+    m.movq(R(rax), Long(3));
+    m.cvtsi2sd(D(xmm1), R(rax));
+    m.subsd(D(xmm0), D(xmm1));
+    m.movq(R(rax), Long(100));
+    m.cvtsi2sd(D(xmm2), R(rax));
+    m.minsd(D(xmm0), D(xmm2));
+    m.maxsd(D(xmm0), D(xmm1));
+    m.cvttsd2si(R(rax), D(xmm0));
+    m.ret(Empty);
+  }
+  do run_test(81, 9) |m| {
+    m.cvtsi2sd(D(xmm0), R(rsi));
+    m.sqrtsd(D(xmm0), D(xmm0));
+    m.cvttsd2si(R(rax), D(xmm0));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn fp_encoding() {
+  do expect_bytes(&[0xf2, 0x0f, 0x10, 0x4f, 0x08]) |m| {
+    m.movsd(D(xmm1), M(rdi, 8));
+  }
+  do expect_bytes(&[0xf2, 0x45, 0x0f, 0x11, 0x08]) |m| {
+    m.movsd(M(r8, 0), D(xmm9));
+  }
+  do expect_bytes(&[0xf3, 0x0f, 0x7e, 0xc1, 0x66, 0x44, 0x0f, 0xd6, 0x17]) |m| {
+    m.movqd(D(xmm0), D(xmm1));
+    m.movqd(M(rdi, 0), D(xmm10));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0x58, 0xca, 0xf2, 0x0f, 0x5c, 0xca]) |m| {
+    m.addsd(D(xmm1), D(xmm2));
+    m.subsd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0x59, 0xca, 0xf2, 0x0f, 0x5e, 0xca]) |m| {
+    m.mulsd(D(xmm1), D(xmm2));
+    m.divsd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0x51, 0xca, 0xf2, 0x0f, 0x5d, 0xca,
+                    0xf2, 0x0f, 0x5f, 0xca]) |m| {
+    m.sqrtsd(D(xmm1), D(xmm2));
+    m.minsd(D(xmm1), D(xmm2));
+    m.maxsd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0xc2, 0xca, 0x02]) |m| {
+    m.cmpsd(D(xmm1), D(xmm2), CmpLe);
+  }
+  do expect_bytes(&[0xf2, 0x0f, 0xc2, 0x47, 0x08, 0x03]) |m| {
+    m.cmpsd(D(xmm0), M(rdi, 8), CmpUnord);
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x54, 0xca, 0x66, 0x0f, 0x56, 0xca,
+                    0x66, 0x0f, 0x57, 0xca]) |m| {
+    m.andpd(D(xmm1), D(xmm2));
+    m.orpd(D(xmm1), D(xmm2));
+    m.xorpd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xf2, 0x48, 0x0f, 0x2a, 0xc0]) |m| {
+    m.cvtsi2sd(D(xmm0), R(rax));
+  }
+  do expect_bytes(&[0xf2, 0x48, 0x0f, 0x2d, 0xc1]) |m| {
+    m.cvtsd2si(R(rax), D(xmm1));
+  }
+  do expect_bytes(&[0xf2, 0x48, 0x0f, 0x2c, 0xc8]) |m| {
+    m.cvttsd2si(R(rcx), D(xmm0));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x0b, 0xc1, 0x09]) |m| {
+    m.roundsd(D(xmm0), D(xmm1), RoundDown);
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x2e, 0xc1]) |m| {
+    m.ucomisd(D(xmm0), D(xmm1));
+  }
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn packed() {
+  do run_test(5, 20) |m| {
+    // Both lanes = arg * 2
+    m.cvtsi2sd(D(xmm0), R(rsi));
+    m.unpcklpd(D(xmm0), D(xmm0));
+    m.addpd(D(xmm0), D(xmm0));
+
+    // Sum the lanes
+    m.movapd(D(xmm1), D(xmm0));
+    m.unpckhpd(D(xmm1), D(xmm1));
+    m.addsd(D(xmm0), D(xmm1));
+    m.cvttsd2si(R(rax), D(xmm0));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn packed_encoding() {
+  do expect_bytes(&[0x0f, 0x28, 0x07, 0x44, 0x0f, 0x29, 0x47, 0x10]) |m| {
+    m.movaps(D(xmm0), M(rdi, 0));
+    m.movaps(M(rdi, 16), D(xmm8));
+  }
+  do expect_bytes(&[0x0f, 0x10, 0xca, 0x66, 0x0f, 0x28, 0x06,
+                    0x66, 0x0f, 0x11, 0x0f]) |m| {
+    m.movups(D(xmm1), D(xmm2));
+    m.movapd(D(xmm0), M(rsi, 0));
+    m.movupd(M(rdi, 0), D(xmm1));
+  }
+  do expect_bytes(&[0x0f, 0x58, 0xca, 0x66, 0x0f, 0x58, 0xca,
+                    0x0f, 0x5c, 0xca, 0x66, 0x0f, 0x59, 0xca]) |m| {
+    m.addps(D(xmm1), D(xmm2));
+    m.addpd(D(xmm1), D(xmm2));
+    m.subps(D(xmm1), D(xmm2));
+    m.mulpd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x0f, 0x5e, 0xca, 0x66, 0x0f, 0x5d, 0xca,
+                    0x0f, 0x5f, 0xca, 0x66, 0x0f, 0x51, 0xca]) |m| {
+    m.divps(D(xmm1), D(xmm2));
+    m.minpd(D(xmm1), D(xmm2));
+    m.maxps(D(xmm1), D(xmm2));
+    m.sqrtpd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x0f, 0xc6, 0xc1, 0x1b,
+                    0x66, 0x44, 0x0f, 0xc6, 0xc9, 0x01]) |m| {
+    m.shufps(D(xmm0), D(xmm1), Byte(0x1b));
+    m.shufpd(D(xmm9), D(xmm1), Byte(1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x14, 0xc1, 0x66, 0x0f, 0x15, 0xc1]) |m| {
+    m.unpcklpd(D(xmm0), D(xmm1));
+    m.unpckhpd(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x0f, 0x12, 0xc1, 0x0f, 0x16, 0xc1]) |m| {
+    m.movhlps(D(xmm0), D(xmm1));
+    m.movlhps(D(xmm0), D(xmm1));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_movhlps(D(xmm0), M(rdi, 0)).is_err());
+  assert!(m.try_shufps(D(xmm0), D(xmm1), Long(1)).is_err());
+}
+
+#[test]
+#[cfg(target_arch = "x86_64")]
+fn simd() {
+  do run_test(0xff00ff, 0xfffa) |m| {
+    // Mask of zero bytes in the argument
+    m.pxor(D(xmm0), D(xmm0));
+    m.pinsrq(D(xmm0), R(rsi), Byte(0));
+    m.pxor(D(xmm1), D(xmm1));
+    m.pcmpeqb(D(xmm1), D(xmm0));
+    m.pmovmskb(R(rax), D(xmm1));
+    m.ret(Empty);
+  }
+}
+
+#[test]
+fn simd_encoding() {
+  do expect_bytes(&[0x66, 0x0f, 0x6f, 0x07,
+                    0xf3, 0x44, 0x0f, 0x7f, 0x4f, 0x10]) |m| {
+    m.movdqa(D(xmm0), M(rdi, 0));
+    m.movdqu(M(rdi, 16), D(xmm9));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xfc, 0xca, 0x66, 0x0f, 0xfe, 0xca,
+                    0x66, 0x0f, 0xfb, 0xca, 0x66, 0x0f, 0xf4, 0xca]) |m| {
+    m.paddb(D(xmm1), D(xmm2));
+    m.paddd(D(xmm1), D(xmm2));
+    m.psubq(D(xmm1), D(xmm2));
+    m.pmuludq(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x38, 0x40, 0xca, 0x66, 0x0f, 0x38, 0x39, 0xca,
+                    0x66, 0x0f, 0x38, 0x3d, 0xca]) |m| {
+    m.pmulld(D(xmm1), D(xmm2));
+    m.pminsd(D(xmm1), D(xmm2));
+    m.pmaxsd(D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x74, 0xc1, 0x66, 0x0f, 0x38, 0x29, 0xc1,
+                    0x66, 0x0f, 0x66, 0xc1, 0x66, 0x0f, 0x38, 0x17, 0xc1]) |m| {
+    m.pcmpeqb(D(xmm0), D(xmm1));
+    m.pcmpeqq(D(xmm0), D(xmm1));
+    m.pcmpgtd(D(xmm0), D(xmm1));
+    m.ptest(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xdb, 0xc1, 0x66, 0x0f, 0xdf, 0xc1,
+                    0x66, 0x0f, 0xeb, 0xc1, 0x66, 0x0f, 0xef, 0xc1]) |m| {
+    m.pand(D(xmm0), D(xmm1));
+    m.pandn(D(xmm0), D(xmm1));
+    m.por(D(xmm0), D(xmm1));
+    m.pxor(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xf1, 0xca, 0x66, 0x0f, 0x72, 0xf1, 0x04]) |m| {
+    m.psllw(D(xmm1), D(xmm2));
+    m.pslld(D(xmm1), Byte(4));
+  }
+  do expect_bytes(&[0x66, 0x41, 0x0f, 0x73, 0xd2, 0x01,
+                    0x66, 0x0f, 0x72, 0xe0, 0x1f]) |m| {
+    m.psrlq(D(xmm10), Byte(1));
+    m.psrad(D(xmm0), Byte(31));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x70, 0xc1, 0x1b,
+                    0x66, 0x0f, 0x38, 0x00, 0xc1]) |m| {
+    m.pshufd(D(xmm0), D(xmm1), Byte(0x1b));
+    m.pshufb(D(xmm0), D(xmm1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x20, 0xc0, 0x03,
+                    0x66, 0x0f, 0xc4, 0x0f, 0x02]) |m| {
+    m.pinsrb(D(xmm0), R(rax), Byte(3));
+    m.pinsrw(D(xmm1), M(rdi, 0), Byte(2));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x22, 0xc1, 0x01,
+                    0x66, 0x49, 0x0f, 0x3a, 0x22, 0xc0, 0x01]) |m| {
+    m.pinsrd(D(xmm0), R(rcx), Byte(1));
+    m.pinsrq(D(xmm0), R(r8), Byte(1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x14, 0xc8, 0x03,
+                    0x66, 0x0f, 0xc5, 0xc1, 0x02]) |m| {
+    m.pextrb(R(rax), D(xmm1), Byte(3));
+    m.pextrw(R(rax), D(xmm1), Byte(2));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0x3a, 0x16, 0x17, 0x01,
+                    0x66, 0x48, 0x0f, 0x3a, 0x16, 0xc8, 0x01]) |m| {
+    m.pextrd(M(rdi, 0), D(xmm2), Byte(1));
+    m.pextrq(R(rax), D(xmm1), Byte(1));
+  }
+  do expect_bytes(&[0x66, 0x0f, 0xd7, 0xc1]) |m| {
+    m.pmovmskb(R(rax), D(xmm1));
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_pmovmskb(R(rax), M(rdi, 0)).is_err());
+  assert!(m.try_pextrw(M(rdi, 0), D(xmm1), Byte(0)).is_err());
+}
+
+#[test]
+fn avx_encoding() {
+  do expect_bytes(&[0xc5, 0xfc, 0x28, 0xc1,
+                    0xc5, 0x7d, 0x11, 0x4f, 0x20]) |m| {
+    m.vmovaps(Y(ymm0), Y(ymm1));
+    m.vmovupd(M(rdi, 32), Y(ymm9));
+  }
+  do expect_bytes(&[0xc5, 0xfd, 0x7f, 0x07, 0xc5, 0xfe, 0x6f, 0x0f,
+                    0xc5, 0x7a, 0x7f, 0x4f, 0x20]) |m| {
+    m.vmovdqa(M(rdi, 0), Y(ymm0));
+    m.vmovdqu(Y(ymm1), M(rdi, 0));
+    m.vmovdqu(M(rdi, 32), D(xmm9));
+  }
+  do expect_bytes(&[0xc5, 0xf5, 0x58, 0xc2, 0xc5, 0xd8, 0x59, 0x1f,
+                    0xc4, 0x41, 0x35, 0x58, 0xc2]) |m| {
+    m.vaddpd(Y(ymm0), Y(ymm1), Y(ymm2));
+    m.vmulps(D(xmm3), D(xmm4), M(rdi, 0));
+    m.vaddpd(Y(ymm8), Y(ymm9), Y(ymm10));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0xf5, 0xb8, 0xc2,
+                    0xc4, 0xe2, 0x71, 0xb8, 0xc2]) |m| {
+    m.vfmadd231pd(Y(ymm0), Y(ymm1), Y(ymm2));
+    m.vfmadd231ps(D(xmm0), D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xc5, 0xf5, 0xfe, 0xc2,
+                    0xc4, 0xe2, 0x71, 0x40, 0xc2]) |m| {
+    m.vpaddd(Y(ymm0), Y(ymm1), Y(ymm2));
+    m.vpmulld(D(xmm0), D(xmm1), D(xmm2));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0x7d, 0x19, 0xc1,
+                    0xc4, 0xe2, 0x7d, 0x18, 0x07,
+                    0xc4, 0xe3, 0xfd, 0x00, 0xc1, 0x1b]) |m| {
+    m.vbroadcastsd(Y(ymm0), D(xmm1));
+    m.vbroadcastss(Y(ymm0), M(rdi, 0));
+    m.vpermq(Y(ymm0), Y(ymm1), Byte(0x1b));
+  }
+  do expect_bytes(&[0xc4, 0xe3, 0x7d, 0x19, 0xc8, 0x01,
+                    0xc4, 0xe3, 0x75, 0x18, 0xc2, 0x01]) |m| {
+    m.vextractf128(D(xmm0), Y(ymm1), Byte(1));
+    m.vinsertf128(Y(ymm0), Y(ymm1), D(xmm2), Byte(1));
+  }
+  do expect_bytes(&[0xc4, 0xe2, 0x6d, 0x90, 0x04, 0x8f,
+                    0xc4, 0x82, 0x69, 0x90, 0x44, 0x88, 0x08]) |m| {
+    m.vpgatherdd(Y(ymm0), VM(rdi, ymm1, Scale4, 0), Y(ymm2));
+    m.vpgatherdd(D(xmm0), VM(r8, ymm9, Scale4, 8), D(xmm2));
+  }
+  do expect_bytes(&[0xc5, 0xf8, 0x77, 0xc5, 0xfc, 0x77]) |m| {
+    m.vzeroupper();
+    m.vzeroall();
+  }
+
+  let mut m = Asm::new();
+  assert!(m.try_vaddpd(Y(ymm0), D(xmm1), Y(ymm2)).is_err());
+  assert!(m.try_vbroadcastsd(D(xmm0), D(xmm1)).is_err());
+  assert!(m.try_vpgatherdd(Y(ymm0), M(rdi, 0), Y(ymm2)).is_err());
+  match m.try_vpgatherdd(Y(ymm0), VM(rdi, ymm0, Scale4, 0), Y(ymm1)) {
+    Err(err) => {
+      assert_eq!(err.to_str(),
+                 ~"vpgatherdd(Y, VM, Y): registers must be distinct");
+    },
+    Ok(()) => fail!()
+  }
+}